@@ -1,4 +1,35 @@
+use std::fmt;
+use std::ops::{Add, Mul};
+use std::str::FromStr;
+
+
 /// An RGBA colour repr.
+///
+/// Parses from and formats to the colour formats BLT understands in markup and configuration,
+/// see [`from_str()`](#method.from_str) for details.
+///
+/// All arithmetic saturates, so channels never wrap around.
+///
+/// # Examples
+///
+/// ```
+/// # use bear_lib_terminal::Color;
+/// assert_eq!("amber".parse(), Ok(Color::AMBER));
+/// assert_eq!("dark amber".parse(), Ok(Color::from_rgb(0xBF, 0x8F, 0x00)));
+/// assert_eq!("lighter blue".parse(), Ok(Color::from_rgb(0x80, 0x80, 0xFF)));
+/// assert_eq!("0x80FF8000".parse(), Ok(Color::from_rgba(0xFF, 0x80, 0x00, 0x80)));
+/// assert_eq!("255,128,0".parse(), Ok(Color::ORANGE));
+/// assert!("blurple".parse::<Color>().is_err());
+///
+/// assert_eq!(Color::ORANGE.to_string(), "#FF8000");
+/// assert_eq!(Color::from_rgba(0xFF, 0x80, 0x00, 0x80).to_string(), "0x80FF8000");
+///
+/// let torchlight = Color::from_rgb(0xFF, 0xC0, 0x80);
+/// assert_eq!(Color::GREY * torchlight, Color::from_rgb(0x80, 0x60, 0x40));
+/// assert_eq!(Color::GREY * 0.5, Color::from_rgb(0x40, 0x40, 0x40));
+/// assert_eq!(Color::GREY + torchlight, Color::WHITE);
+/// assert_eq!(Color::BLACK.lerp(Color::WHITE, 0.25), Color::from_rgb(0x40, 0x40, 0x40));
+/// ```
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Color {
 	pub red: u8,
@@ -8,6 +39,33 @@ pub struct Color {
 }
 
 impl Color {
+	// BLT's named palette, the hues fully saturated at full brightness
+	pub const RED        : Color = Color{red: 0xFF, green: 0x00, blue: 0x00, alpha: 0xFF};
+	pub const FLAME      : Color = Color{red: 0xFF, green: 0x40, blue: 0x00, alpha: 0xFF};
+	pub const ORANGE     : Color = Color{red: 0xFF, green: 0x80, blue: 0x00, alpha: 0xFF};
+	pub const AMBER      : Color = Color{red: 0xFF, green: 0xBF, blue: 0x00, alpha: 0xFF};
+	pub const YELLOW     : Color = Color{red: 0xFF, green: 0xFF, blue: 0x00, alpha: 0xFF};
+	pub const LIME       : Color = Color{red: 0xBF, green: 0xFF, blue: 0x00, alpha: 0xFF};
+	pub const CHARTREUSE : Color = Color{red: 0x80, green: 0xFF, blue: 0x00, alpha: 0xFF};
+	pub const GREEN      : Color = Color{red: 0x00, green: 0xFF, blue: 0x00, alpha: 0xFF};
+	pub const SEA        : Color = Color{red: 0x00, green: 0xFF, blue: 0x80, alpha: 0xFF};
+	pub const TURQUOISE  : Color = Color{red: 0x00, green: 0xFF, blue: 0xBF, alpha: 0xFF};
+	pub const CYAN       : Color = Color{red: 0x00, green: 0xFF, blue: 0xFF, alpha: 0xFF};
+	pub const SKY        : Color = Color{red: 0x00, green: 0xBF, blue: 0xFF, alpha: 0xFF};
+	pub const AZURE      : Color = Color{red: 0x00, green: 0x80, blue: 0xFF, alpha: 0xFF};
+	pub const BLUE       : Color = Color{red: 0x00, green: 0x00, blue: 0xFF, alpha: 0xFF};
+	pub const HAN        : Color = Color{red: 0x40, green: 0x00, blue: 0xFF, alpha: 0xFF};
+	pub const VIOLET     : Color = Color{red: 0x80, green: 0x00, blue: 0xFF, alpha: 0xFF};
+	pub const PURPLE     : Color = Color{red: 0xBF, green: 0x00, blue: 0xFF, alpha: 0xFF};
+	pub const FUCHSIA    : Color = Color{red: 0xFF, green: 0x00, blue: 0xFF, alpha: 0xFF};
+	pub const MAGENTA    : Color = Color{red: 0xFF, green: 0x00, blue: 0xBF, alpha: 0xFF};
+	pub const PINK       : Color = Color{red: 0xFF, green: 0x00, blue: 0x80, alpha: 0xFF};
+	pub const CRIMSON    : Color = Color{red: 0xFF, green: 0x00, blue: 0x40, alpha: 0xFF};
+	pub const GREY       : Color = Color{red: 0x80, green: 0x80, blue: 0x80, alpha: 0xFF};
+	pub const BLACK      : Color = Color{red: 0x00, green: 0x00, blue: 0x00, alpha: 0xFF};
+	pub const WHITE      : Color = Color{red: 0xFF, green: 0xFF, blue: 0xFF, alpha: 0xFF};
+	pub const TRANSPARENT: Color = Color{red: 0x00, green: 0x00, blue: 0x00, alpha: 0x00};
+
 	/// Equivalent to [`from_rgba()`](fn.from_rgba.html) with full opacity
 	pub fn from_rgb(red: u8, green: u8, blue: u8) -> Self {
 		Color{
@@ -26,4 +84,309 @@ impl Color {
 			alpha: alpha,
 		}
 	}
+
+	/// Construct an opaque colour from its hue in degrees, and saturation and value in `[0, 1]`.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use bear_lib_terminal::Color;
+	/// assert_eq!(Color::from_hsv(45.0, 1.0, 1.0), Color::AMBER);
+	/// assert_eq!(Color::from_hsv(0.0, 0.0, 0.5), Color::GREY);
+	/// assert_eq!(Color::GREEN.to_hsv(), (120.0, 1.0, 1.0));
+	/// ```
+	pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Self {
+		let chroma = clamp(value) * clamp(saturation);
+		let (red, green, blue) = from_hue(hue, chroma);
+		let lightness = clamp(value) - chroma;
+		Color::from_rgb(to_channel(red + lightness), to_channel(green + lightness), to_channel(blue + lightness))
+	}
+
+	/// Construct an opaque colour from its hue in degrees, and saturation and lightness in `[0, 1]`.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use bear_lib_terminal::Color;
+	/// assert_eq!(Color::from_hsl(240.0, 1.0, 0.5), Color::BLUE);
+	/// assert_eq!(Color::from_hsl(240.0, 1.0, 0.75), Color::from_rgb(0x80, 0x80, 0xFF));
+	/// assert_eq!(Color::BLUE.to_hsl(), (240.0, 1.0, 0.5));
+	/// ```
+	pub fn from_hsl(hue: f32, saturation: f32, lightness: f32) -> Self {
+		let chroma = (1.0 - (2.0 * clamp(lightness) - 1.0).abs()) * clamp(saturation);
+		let (red, green, blue) = from_hue(hue, chroma);
+		let lightness = clamp(lightness) - chroma / 2.0;
+		Color::from_rgb(to_channel(red + lightness), to_channel(green + lightness), to_channel(blue + lightness))
+	}
+
+	/// Get the colour's hue in degrees in `[0, 360)`, and saturation and value in `[0, 1]`, disregarding alpha.
+	pub fn to_hsv(&self) -> (f32, f32, f32) {
+		let (hue, max, min) = self.hue();
+		let saturation = if max == 0.0 {0.0} else {(max - min) / max};
+		(hue, saturation, max)
+	}
+
+	/// Get the colour's hue in degrees in `[0, 360)`, and saturation and lightness in `[0, 1]`, disregarding alpha.
+	pub fn to_hsl(&self) -> (f32, f32, f32) {
+		let (hue, max, min) = self.hue();
+		let lightness = (max + min) / 2.0;
+		let saturation = if max == min {0.0} else {(max - min) / (1.0 - (2.0 * lightness - 1.0).abs())};
+		(hue, saturation, lightness)
+	}
+
+	/// Linearly interpolate all channels towards the other colour, `amount` being clamped to `[0, 1]`.
+	pub fn lerp(self, other: Color, amount: f32) -> Color {
+		let amount = clamp(amount);
+		let mix = |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * amount).round() as u8;
+		Color::from_rgba(mix(self.red, other.red), mix(self.green, other.green), mix(self.blue, other.blue), mix(self.alpha, other.alpha))
+	}
+
+	/// Composite the other colour over this one, the other's alpha being its opacity.
+	///
+	/// The resulting alpha is that of alpha-over compositing, regardless of the mode.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use bear_lib_terminal::{BlendMode, Color};
+	/// let shadow = Color::from_rgba(0x00, 0x00, 0x00, 0x80);
+	/// assert_eq!(Color::WHITE.blend(shadow, BlendMode::Normal), Color::from_rgb(0x7F, 0x7F, 0x7F));
+	/// assert_eq!(Color::GREY.blend(Color::GREY, BlendMode::Screen), Color::from_rgb(0xC0, 0xC0, 0xC0));
+	/// assert_eq!(Color::RED.blend(Color::from_rgba(0xFF, 0xFF, 0xFF, 0x00), BlendMode::Multiply), Color::RED);
+	/// ```
+	pub fn blend(self, over: Color, mode: BlendMode) -> Color {
+		let backdrop = self;
+		let opacity = over.alpha as f32 / 255.0;
+		let mix = |below: u8, above: u8| {
+			let (b, a) = (below as f32 / 255.0, above as f32 / 255.0);
+			let blended = match mode {
+				BlendMode::Normal   => a,
+				BlendMode::Multiply => b * a,
+				BlendMode::Screen   => 1.0 - (1.0 - b) * (1.0 - a),
+				BlendMode::Overlay  => if b < 0.5 {2.0 * b * a} else {1.0 - 2.0 * (1.0 - b) * (1.0 - a)},
+				BlendMode::Add      => (b + a).min(1.0),
+				BlendMode::Subtract => (b - a).max(0.0),
+				BlendMode::Darken   => b.min(a),
+				BlendMode::Lighten  => b.max(a),
+			};
+			to_channel(b + (blended - b) * opacity)
+		};
+
+		let alpha = opacity + backdrop.alpha as f32 / 255.0 * (1.0 - opacity);
+		Color::from_rgba(mix(backdrop.red, over.red), mix(backdrop.green, over.green), mix(backdrop.blue, over.blue), to_channel(alpha))
+	}
+
+	/// Get the hue, and the maximal and minimal RGB channels in `[0, 1]`.
+	fn hue(&self) -> (f32, f32, f32) {
+		let (red, green, blue) = (self.red as f32 / 255.0, self.green as f32 / 255.0, self.blue as f32 / 255.0);
+		let max = red.max(green).max(blue);
+		let min = red.min(green).min(blue);
+		let chroma = max - min;
+
+		let hue = if chroma == 0.0 {
+			0.0
+		} else if max == red {
+			60.0 * ((green - blue) / chroma)
+		} else if max == green {
+			60.0 * ((blue - red) / chroma + 2.0)
+		} else {
+			60.0 * ((red - green) / chroma + 4.0)
+		};
+		(round_hue(hue), max, min)
+	}
+}
+
+
+/// How [`Color::blend()`](struct.Color.html#method.blend) combines the colour on top with the one below.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BlendMode {
+	/// The colour on top replaces the one below.
+	Normal,
+	/// Darkens, like shining a light of the colour on top on the one below.
+	Multiply,
+	/// Lightens, the inverse of multiplying the inverses.
+	Screen,
+	/// Multiplies dark and screens light colours below.
+	Overlay,
+	/// Sums the colours.
+	Add,
+	/// Subtracts the colour on top from the one below.
+	Subtract,
+	/// Takes the darker of each channel.
+	Darken,
+	/// Takes the lighter of each channel.
+	Lighten,
+}
+
+
+/// Saturating channel-wise addition.
+impl Add for Color {
+	type Output = Color;
+
+	fn add(self, other: Color) -> Color {
+		Color::from_rgba(self.red.saturating_add(other.red), self.green.saturating_add(other.green), self.blue.saturating_add(other.blue),
+		                 self.alpha.saturating_add(other.alpha))
+	}
+}
+
+/// Brightness scaling of the RGB channels, leaving alpha as-is.
+impl Mul<f32> for Color {
+	type Output = Color;
+
+	fn mul(self, factor: f32) -> Color {
+		let scale = |channel: u8| to_channel(channel as f32 / 255.0 * factor);
+		Color::from_rgba(scale(self.red), scale(self.green), scale(self.blue), self.alpha)
+	}
+}
+
+/// Channel-wise multiplication, as if the channels were in `[0, 1]`.
+impl Mul for Color {
+	type Output = Color;
+
+	fn mul(self, other: Color) -> Color {
+		let multiply = |lhs: u8, rhs: u8| ((lhs as u32 * rhs as u32 + 127) / 255) as u8;
+		Color::from_rgba(multiply(self.red, other.red), multiply(self.green, other.green), multiply(self.blue, other.blue),
+		                 multiply(self.alpha, other.alpha))
+	}
+}
+
+impl fmt::Display for Color {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		if self.alpha == 0xFF {
+			write!(formatter, "#{:02X}{:02X}{:02X}", self.red, self.green, self.blue)
+		} else {
+			write!(formatter, "0x{:02X}{:02X}{:02X}{:02X}", self.alpha, self.red, self.green, self.blue)
+		}
+	}
+}
+
+/// Accepts, case-insensitively:
+///
+///   * a palette name, like `amber` or `gray`, optionally prefixed with a shade:
+///     `lightest`, `lighter`, `light`, `dark`, `darker` or `darkest`,
+///   * `#RRGGBB` or `#AARRGGBB`,
+///   * `0xAARRGGBB`,
+///   * `R,G,B` or `A,R,G,B`, in decimal.
+impl FromStr for Color {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Color, String> {
+		let s = s.trim();
+		let invalid = || format!("invalid colour \"{}\"", s);
+
+		if let Some(hex) = s.strip_prefix('#') {
+			let value = u32::from_str_radix(hex, 16).map_err(|_| invalid())?;
+			return match hex.len() {
+				6 => Ok(from_argb(value | 0xFF000000)),
+				8 => Ok(from_argb(value)),
+				_ => Err(invalid()),
+			};
+		}
+		if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+			return u32::from_str_radix(hex, 16).map(from_argb).map_err(|_| invalid());
+		}
+		if s.contains(',') {
+			let components = s.split(',').map(|component| component.trim().parse::<u8>()).collect::<Result<Vec<_>, _>>().map_err(|_| invalid())?;
+			return match components[..] {
+				[red, green, blue]        => Ok(Color::from_rgba(red, green, blue, 0xFF)),
+				[alpha, red, green, blue] => Ok(Color::from_rgba(red, green, blue, alpha)),
+				_                         => Err(invalid()),
+			};
+		}
+
+		let name = s.to_lowercase();
+		let mut words = name.split_whitespace();
+		let (shade, name) = match (words.next(), words.next(), words.next()) {
+			(Some(name), None, None)        => (None, name),
+			(Some(shade), Some(name), None) => (Some(shade), name),
+			_                               => return Err(invalid()),
+		};
+		let color = PALETTE.iter().find(|&&(palette_name, _)| palette_name == name).map(|&(_, color)| color).ok_or_else(invalid)?;
+
+		match shade {
+			None             => Ok(color),
+			Some("lightest") => Ok(lighten(color, 0.75)),
+			Some("lighter")  => Ok(lighten(color, 0.5)),
+			Some("light")    => Ok(lighten(color, 0.25)),
+			Some("dark")     => Ok(darken(color, 0.75)),
+			Some("darker")   => Ok(darken(color, 0.5)),
+			Some("darkest")  => Ok(darken(color, 0.25)),
+			Some(_)          => Err(invalid()),
+		}
+	}
+}
+
+
+static PALETTE: [(&str, Color); 26] = [
+	("red",         Color::RED),
+	("flame",       Color::FLAME),
+	("orange",      Color::ORANGE),
+	("amber",       Color::AMBER),
+	("yellow",      Color::YELLOW),
+	("lime",        Color::LIME),
+	("chartreuse",  Color::CHARTREUSE),
+	("green",       Color::GREEN),
+	("sea",         Color::SEA),
+	("turquoise",   Color::TURQUOISE),
+	("cyan",        Color::CYAN),
+	("sky",         Color::SKY),
+	("azure",       Color::AZURE),
+	("blue",        Color::BLUE),
+	("han",         Color::HAN),
+	("violet",      Color::VIOLET),
+	("purple",      Color::PURPLE),
+	("fuchsia",     Color::FUCHSIA),
+	("magenta",     Color::MAGENTA),
+	("pink",        Color::PINK),
+	("crimson",     Color::CRIMSON),
+	("grey",        Color::GREY),
+	("gray",        Color::GREY),
+	("black",       Color::BLACK),
+	("white",       Color::WHITE),
+	("transparent", Color::TRANSPARENT),
+];
+
+fn from_argb(value: u32) -> Color {
+	Color::from_rgba((value >> 16) as u8, (value >> 8) as u8, value as u8, (value >> 24) as u8)
+}
+
+/// Mix the colour with white, `amount` being white's share.
+fn lighten(color: Color, amount: f32) -> Color {
+	let mix = |component: u8| (component as f32 + (0xFF as f32 - component as f32) * amount).round() as u8;
+	Color::from_rgba(mix(color.red), mix(color.green), mix(color.blue), color.alpha)
+}
+
+/// Scale the colour's brightness by `amount`.
+fn darken(color: Color, amount: f32) -> Color {
+	let scale = |component: u8| (component as f32 * amount).round() as u8;
+	Color::from_rgba(scale(color.red), scale(color.green), scale(color.blue), color.alpha)
+}
+
+fn clamp(value: f32) -> f32 {
+	value.clamp(0.0, 1.0)
+}
+
+/// Convert a channel in `[0, 1]` to a `u8`, clamping it first.
+fn to_channel(value: f32) -> u8 {
+	(clamp(value) * 255.0).round() as u8
+}
+
+/// Get the RGB channels, without the lightness component, for the hue in degrees and the specified chroma.
+fn from_hue(hue: f32, chroma: f32) -> (f32, f32, f32) {
+	let sector = round_hue(hue) / 60.0;
+	let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+	match sector as i32 {
+		0 => (chroma, x, 0.0),
+		1 => (x, chroma, 0.0),
+		2 => (0.0, chroma, x),
+		3 => (0.0, x, chroma),
+		4 => (x, 0.0, chroma),
+		_ => (chroma, 0.0, x),
+	}
+}
+
+/// Wrap the hue into `[0, 360)`.
+fn round_hue(hue: f32) -> f32 {
+	let hue = hue % 360.0;
+	if hue < 0.0 {hue + 360.0} else {hue}
 }
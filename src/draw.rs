@@ -0,0 +1,390 @@
+//! Rasterisers for lines, ellipses, rectangles and box-drawing borders, on top of [`put()`](../terminal/fn.put.html).
+//!
+//! Borders and box lines pick up the box-drawing characters already in the terminal on the current layer,
+//! joining into the proper junction glyph.
+//!
+//! # Examples
+//!
+//! ```
+//! use bear_lib_terminal::draw::{self, BorderStyle};
+//! use bear_lib_terminal::terminal;
+//! use bear_lib_terminal::terminal::backend::Headless;
+//! use bear_lib_terminal::geometry::{Point, Rect};
+//!
+//! let backend = Headless::new();
+//! terminal::open_with(backend.clone(), "draw example", 8, 4).unwrap();
+//! draw::border(Rect::from_values(0, 0, 8, 4), BorderStyle::Light);
+//! draw::vline(Point::new(3, 0), 4, BorderStyle::Heavy);
+//! draw::hline(Point::new(3, 2), 5, BorderStyle::Light);
+//! draw::line(Point::new(1, 1), Point::new(2, 2), '\\');
+//! assert_eq!(backend.line(0, 0), "┌──┰───┐");
+//! assert_eq!(backend.line(0, 1), "│\\ ┃   │");
+//! assert_eq!(backend.line(0, 2), "│ \\┠───┤");
+//! assert_eq!(backend.line(0, 3), "└──┸───┘");
+//! terminal::close();
+//! ```
+
+
+use geometry::{Point, Rect, Size};
+use terminal;
+
+
+/// The look of box-drawing lines.
+///
+/// # Examples
+///
+/// ```
+/// use bear_lib_terminal::draw::{self, BorderStyle};
+/// use bear_lib_terminal::terminal;
+/// use bear_lib_terminal::terminal::backend::Headless;
+/// use bear_lib_terminal::geometry::{Point, Rect};
+///
+/// let backend = Headless::new();
+/// terminal::open_with(backend.clone(), "border style example", 5, 3).unwrap();
+/// draw::border(Rect::from_values(0, 0, 5, 3), BorderStyle::Rounded);
+/// draw::vline(Point::new(2, 0), 3, BorderStyle::Double);
+/// assert_eq!(backend.line(0, 0), "╭─╥─╮");
+/// assert_eq!(backend.line(0, 1), "│ ║ │");
+/// assert_eq!(backend.line(0, 2), "╰─╨─╯");
+///
+/// draw::border(Rect::from_values(0, 0, 5, 3), BorderStyle::Ascii);
+/// assert_eq!(backend.line(0, 0), "+-+-+");
+/// terminal::close();
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BorderStyle {
+	/// `┌─┐`
+	Light,
+	/// `┏━┓`
+	Heavy,
+	/// `╔═╗`
+	Double,
+	/// `╭─╮`, light lines with rounded corners.
+	Rounded,
+	/// `+-+`
+	Ascii,
+}
+
+
+/// Put the character on every point of the line between the two points, both inclusive.
+pub fn line(from: Point, to: Point, cell: char) {
+	for point in line_points(from, to) {
+		terminal::put(point, cell);
+	}
+}
+
+/// Iterate over the points of the [Bresenham line](https://en.wikipedia.org/wiki/Bresenham%27s_line_algorithm)
+/// between the two points, both inclusive.
+///
+/// # Examples
+///
+/// ```
+/// # use bear_lib_terminal::draw;
+/// # use bear_lib_terminal::geometry::Point;
+/// assert_eq!(draw::line_points(Point::new(0, 0), Point::new(4, 2)).collect::<Vec<_>>(),
+///            vec![Point::new(0, 0), Point::new(1, 1), Point::new(2, 1), Point::new(3, 2), Point::new(4, 2)]);
+/// ```
+pub fn line_points(from: Point, to: Point) -> LinePoints {
+	let delta_x = (to.x - from.x).abs();
+	let delta_y = -(to.y - from.y).abs();
+	LinePoints{
+		next: Some(from),
+		to: to,
+		delta_x: delta_x,
+		delta_y: delta_y,
+		step_x: if from.x < to.x {1} else {-1},
+		step_y: if from.y < to.y {1} else {-1},
+		error: delta_x + delta_y,
+	}
+}
+
+/// Outline a circle of the specified radius.
+pub fn circle(center: Point, radius: i32, cell: char) {
+	ellipse(center, Size::new(radius, radius), cell);
+}
+
+/// Fill a circle of the specified radius.
+pub fn fill_circle(center: Point, radius: i32, cell: char) {
+	fill_ellipse(center, Size::new(radius, radius), cell);
+}
+
+/// Outline an ellipse with the specified horizontal and vertical radii.
+pub fn ellipse(center: Point, radii: Size, cell: char) {
+	for point in ellipse_points(center, radii) {
+		terminal::put(point, cell);
+	}
+}
+
+/// Fill an ellipse with the specified horizontal and vertical radii.
+pub fn fill_ellipse(center: Point, radii: Size, cell: char) {
+	for point in filled_ellipse_points(center, radii) {
+		terminal::put(point, cell);
+	}
+}
+
+/// Get the points on the outline of an ellipse with the specified horizontal and vertical radii, row by row.
+///
+/// The outline consists of exactly those points of the [filled ellipse](fn.filled_ellipse_points.html)
+/// with a horizontal or vertical neighbour outside it.
+///
+/// # Examples
+///
+/// ```
+/// # use bear_lib_terminal::draw;
+/// # use bear_lib_terminal::geometry::{Point, Size};
+/// let center = Point::new(5, 5);
+/// assert_eq!(draw::ellipse_points(center, Size::new(0, 0)), vec![center]);
+/// assert_eq!(draw::ellipse_points(center, Size::new(1, 1)).len(), 8);
+/// assert_eq!(draw::filled_ellipse_points(center, Size::new(1, 1)).len(), 9);
+/// assert!(draw::ellipse_points(center, Size::new(4, 2)).contains(&Point::new(9, 5)));
+/// ```
+pub fn ellipse_points(center: Point, radii: Size) -> Vec<Point> {
+	let inside = |point: Point| in_ellipse(point.x - center.x, point.y - center.y, radii);
+	filled_ellipse_points(center, radii).into_iter()
+		.filter(|&point| !inside(Point::new(point.x - 1, point.y)) || !inside(Point::new(point.x + 1, point.y)) ||
+		                 !inside(Point::new(point.x, point.y - 1)) || !inside(Point::new(point.x, point.y + 1)))
+		.collect()
+}
+
+/// Get the points within an ellipse with the specified horizontal and vertical radii, row by row.
+pub fn filled_ellipse_points(center: Point, radii: Size) -> Vec<Point> {
+	Rect::from_size(Point::new(center.x - radii.width, center.y - radii.height), Size::new(radii.width * 2 + 1, radii.height * 2 + 1))
+		.points()
+		.filter(|point| in_ellipse(point.x - center.x, point.y - center.y, radii))
+		.collect()
+}
+
+/// Outline the rectangle, on its outermost cells.
+pub fn rect(area: Rect, cell: char) {
+	for point in area.points() {
+		if on_edge(area, point) {
+			terminal::put(point, cell);
+		}
+	}
+}
+
+/// Fill the rectangle.
+pub fn fill_rect(area: Rect, cell: char) {
+	for point in area.points() {
+		terminal::put(point, cell);
+	}
+}
+
+/// Draw a box border on the rectangle's outermost cells, joining it with any lines already there.
+pub fn border(area: Rect, style: BorderStyle) {
+	let right = area.bottom_right.x - 1;
+	let bottom = area.bottom_right.y - 1;
+	for point in area.points().filter(|&point| on_edge(area, point)) {
+		let horizontal = point.y == area.top_left.y || point.y == bottom;
+		let vertical = point.x == area.top_left.x || point.x == right;
+		let arms = Arms{
+			up: vertical && point.y > area.top_left.y,
+			right: horizontal && point.x < right,
+			down: vertical && point.y < bottom,
+			left: horizontal && point.x > area.top_left.x,
+		};
+		join(point, arms, arms, style);
+	}
+}
+
+/// Draw a horizontal box line `length` cells long rightwards, joining it with any lines already there.
+///
+/// The ends only reach into cells with lines already in them, so that a line ending on a border forms a `├` rather than a `┼`.
+pub fn hline(from: Point, length: i32, style: BorderStyle) {
+	for i in 0..length {
+		let arms = Arms{up: false, right: i < length - 1, down: false, left: i > 0};
+		join(Point::new(from.x + i, from.y), arms, Arms{up: false, right: true, down: false, left: true}, style);
+	}
+}
+
+/// Draw a vertical box line `length` cells long downwards, joining it with any lines already there.
+///
+/// The ends only reach into cells with lines already in them, so that a line ending on a border forms a `┬` rather than a `┼`.
+pub fn vline(from: Point, length: i32, style: BorderStyle) {
+	for i in 0..length {
+		let arms = Arms{up: i > 0, right: false, down: i < length - 1, left: false};
+		join(Point::new(from.x, from.y + i), arms, Arms{up: true, right: false, down: true, left: false}, style);
+	}
+}
+
+
+/// Iterator over the points of a line, obtained from [`line_points()`](fn.line_points.html).
+#[derive(Clone, Debug)]
+pub struct LinePoints {
+	next: Option<Point>,
+	to: Point,
+	delta_x: i32,
+	delta_y: i32,
+	step_x: i32,
+	step_y: i32,
+	error: i32,
+}
+
+impl Iterator for LinePoints {
+	type Item = Point;
+
+	fn next(&mut self) -> Option<Point> {
+		let point = self.next?;
+		if point == self.to {
+			self.next = None;
+			return Some(point);
+		}
+
+		let mut next = point;
+		let doubled = self.error * 2;
+		if doubled >= self.delta_y {
+			self.error += self.delta_y;
+			next.x += self.step_x;
+		}
+		if doubled <= self.delta_x {
+			self.error += self.delta_x;
+			next.y += self.step_y;
+		}
+		self.next = Some(next);
+		Some(point)
+	}
+}
+
+
+/// The weight of a single box-drawing arm.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Weight {
+	None,
+	Light,
+	Heavy,
+	Double,
+}
+
+/// Which arms a box-drawing cell reaches out with.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Arms {
+	up: bool,
+	right: bool,
+	down: bool,
+	left: bool,
+}
+
+/// The arms of a box-drawing glyph, in up, right, down, left order.
+type Glyph = [Weight; 4];
+
+/// All box-drawing glyphs made of plain arms.
+static GLYPHS: &[(char, Glyph)] = {
+	use self::Weight::{None as O, Light as L, Heavy as H, Double as D};
+	&[
+		('─', [O, L, O, L]), ('━', [O, H, O, H]), ('│', [L, O, L, O]), ('┃', [H, O, H, O]),
+		('┌', [O, L, L, O]), ('┍', [O, H, L, O]), ('┎', [O, L, H, O]), ('┏', [O, H, H, O]),
+		('┐', [O, O, L, L]), ('┑', [O, O, L, H]), ('┒', [O, O, H, L]), ('┓', [O, O, H, H]),
+		('└', [L, L, O, O]), ('┕', [L, H, O, O]), ('┖', [H, L, O, O]), ('┗', [H, H, O, O]),
+		('┘', [L, O, O, L]), ('┙', [L, O, O, H]), ('┚', [H, O, O, L]), ('┛', [H, O, O, H]),
+		('├', [L, L, L, O]), ('┝', [L, H, L, O]), ('┞', [H, L, L, O]), ('┟', [L, L, H, O]),
+		('┠', [H, L, H, O]), ('┡', [H, H, L, O]), ('┢', [L, H, H, O]), ('┣', [H, H, H, O]),
+		('┤', [L, O, L, L]), ('┥', [L, O, L, H]), ('┦', [H, O, L, L]), ('┧', [L, O, H, L]),
+		('┨', [H, O, H, L]), ('┩', [H, O, L, H]), ('┪', [L, O, H, H]), ('┫', [H, O, H, H]),
+		('┬', [O, L, L, L]), ('┭', [O, L, L, H]), ('┮', [O, H, L, L]), ('┯', [O, H, L, H]),
+		('┰', [O, L, H, L]), ('┱', [O, L, H, H]), ('┲', [O, H, H, L]), ('┳', [O, H, H, H]),
+		('┴', [L, L, O, L]), ('┵', [L, L, O, H]), ('┶', [L, H, O, L]), ('┷', [L, H, O, H]),
+		('┸', [H, L, O, L]), ('┹', [H, L, O, H]), ('┺', [H, H, O, L]), ('┻', [H, H, O, H]),
+		('┼', [L, L, L, L]), ('┽', [L, L, L, H]), ('┾', [L, H, L, L]), ('┿', [L, H, L, H]),
+		('╀', [H, L, L, L]), ('╁', [L, L, H, L]), ('╂', [H, L, H, L]), ('╃', [H, L, L, H]),
+		('╄', [H, H, L, L]), ('╅', [L, L, H, H]), ('╆', [L, H, H, L]), ('╇', [H, H, L, H]),
+		('╈', [L, H, H, H]), ('╉', [H, L, H, H]), ('╊', [H, H, H, L]), ('╋', [H, H, H, H]),
+		('═', [O, D, O, D]), ('║', [D, O, D, O]),
+		('╒', [O, D, L, O]), ('╓', [O, L, D, O]), ('╔', [O, D, D, O]),
+		('╕', [O, O, L, D]), ('╖', [O, O, D, L]), ('╗', [O, O, D, D]),
+		('╘', [L, D, O, O]), ('╙', [D, L, O, O]), ('╚', [D, D, O, O]),
+		('╛', [L, O, O, D]), ('╜', [D, O, O, L]), ('╝', [D, O, O, D]),
+		('╞', [L, D, L, O]), ('╟', [D, L, D, O]), ('╠', [D, D, D, O]),
+		('╡', [L, O, L, D]), ('╢', [D, O, D, L]), ('╣', [D, O, D, D]),
+		('╤', [O, D, L, D]), ('╥', [O, L, D, L]), ('╦', [O, D, D, D]),
+		('╧', [L, D, O, D]), ('╨', [D, L, O, L]), ('╩', [D, D, O, D]),
+		('╪', [L, D, L, D]), ('╫', [D, L, D, L]), ('╬', [D, D, D, D]),
+		('╴', [O, O, O, L]), ('╵', [L, O, O, O]), ('╶', [O, L, O, O]), ('╷', [O, O, L, O]),
+		('╸', [O, O, O, H]), ('╹', [H, O, O, O]), ('╺', [O, H, O, O]), ('╻', [O, O, H, O]),
+		('╼', [O, H, O, L]), ('╽', [L, O, H, O]), ('╾', [O, L, O, H]), ('╿', [H, O, L, O]),
+	]
+};
+
+/// The rounded corners, preferred over the square ones for the rounded style.
+static ROUNDED: &[(char, Glyph)] = {
+	use self::Weight::{None as O, Light as L};
+	&[('╭', [O, L, L, O]), ('╮', [O, O, L, L]), ('╯', [L, O, O, L]), ('╰', [L, L, O, O])]
+};
+
+/// The ASCII lines, `+` standing for any junction.
+static ASCII: &[(char, Glyph)] = {
+	use self::Weight::{None as O, Light as L};
+	&[('-', [O, L, O, L]), ('|', [L, O, L, O]), ('+', [L, L, L, L])]
+};
+
+
+/// Put the box-drawing glyph with the specified arms merged into the one already there,
+/// or with the `standalone` arms if there's none.
+fn join(point: Point, arms: Arms, standalone: Arms, style: BorderStyle) {
+	let existing = terminal::pick(point, 0).ok().and_then(glyph);
+	let weight = match style {
+		BorderStyle::Light | BorderStyle::Rounded | BorderStyle::Ascii => Weight::Light,
+		BorderStyle::Heavy                                             => Weight::Heavy,
+		BorderStyle::Double                                            => Weight::Double,
+	};
+
+	let mut merged = existing.unwrap_or([Weight::None; 4]);
+	let arms = if existing.is_some() {arms} else {standalone};
+	for (idx, &arm) in [arms.up, arms.right, arms.down, arms.left].iter().enumerate() {
+		if arm {
+			merged[idx] = weight;
+		}
+	}
+
+	if let Some(cell) = to_char(merged, style) {
+		terminal::put(point, cell);
+	}
+}
+
+/// Get the arms of the specified box-drawing character.
+fn glyph(cell: char) -> Option<Glyph> {
+	GLYPHS.iter().chain(ROUNDED).chain(ASCII).find(|&&(glyph, _)| glyph == cell).map(|&(_, arms)| arms)
+}
+
+/// Get the character in the table with exactly the specified arms.
+fn find(table: &[(char, Glyph)], arms: Glyph) -> Option<char> {
+	table.iter().find(|&&(_, glyph_arms)| glyph_arms == arms).map(|&(glyph, _)| glyph)
+}
+
+/// Get the box-drawing character closest to the specified arms, degrading heavy then double arms to light ones
+/// where Unicode has no glyph for the combination.
+fn to_char(arms: Glyph, style: BorderStyle) -> Option<char> {
+	if arms == [Weight::None; 4] {
+		return None;
+	}
+
+	if style == BorderStyle::Ascii {
+		let horizontal = arms[1] != Weight::None || arms[3] != Weight::None;
+		let vertical = arms[0] != Weight::None || arms[2] != Weight::None;
+		return Some(match (horizontal, vertical) {
+			(true, true)  => '+',
+			(true, false) => '-',
+			_             => '|',
+		});
+	}
+
+	if style == BorderStyle::Rounded {
+		if let Some(glyph) = find(ROUNDED, arms) {
+			return Some(glyph);
+		}
+	}
+
+	let without_heavy = arms.map(|arm| if arm == Weight::Heavy {Weight::Light} else {arm});
+	let all_light = without_heavy.map(|arm| if arm == Weight::Double {Weight::Light} else {arm});
+	find(GLYPHS, arms).or_else(|| find(GLYPHS, without_heavy)).or_else(|| find(GLYPHS, all_light))
+}
+
+/// Whether the point is in the ellipse around the origin, the radii extended by half a cell to round it off.
+fn in_ellipse(x: i32, y: i32, radii: Size) -> bool {
+	let (x, y) = (x as i64 * 2, y as i64 * 2);
+	let (width, height) = (radii.width as i64 * 2 + 1, radii.height as i64 * 2 + 1);
+	x * x * height * height + y * y * width * width <= width * width * height * height
+}
+
+fn on_edge(area: Rect, point: Point) -> bool {
+	point.x == area.top_left.x || point.y == area.top_left.y || point.x == area.bottom_right.x - 1 || point.y == area.bottom_right.y - 1
+}
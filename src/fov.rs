@@ -0,0 +1,300 @@
+//! Field-of-view and line-of-sight calculations over a user-supplied opacity map.
+//!
+//! The opacity callbacks are only ever called for points within the bounds, everything outside them counting as opaque.
+//! Opaque cells are visible themselves, so walls bordering the visible area are lit.
+//!
+//! # Examples
+//!
+//! ```
+//! use bear_lib_terminal::fov;
+//! use bear_lib_terminal::geometry::{Point, Rect};
+//!
+//! let map = ["#######",
+//!            "#.....#",
+//!            "#..#..#",
+//!            "#.....#",
+//!            "#######"];
+//! let is_opaque = |point: Point| map[point.y as usize].as_bytes()[point.x as usize] == b'#';
+//! let bounds = Rect::from_values(0, 0, 7, 5);
+//!
+//! let visible = fov::shadowcast(Point::new(2, 2), 10, bounds, &is_opaque);
+//! assert!(visible.is_visible(Point::new(3, 2)));
+//! assert!(!visible.is_visible(Point::new(4, 2)));
+//! assert!(!fov::line_of_sight(Point::new(1, 2), Point::new(5, 2), bounds, &is_opaque));
+//! ```
+
+
+use draw;
+use geometry::{Point, Rect};
+
+
+/// The set of points visible from somewhere, bounded by a `Rect`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Visibility {
+	area: Rect,
+	visible: Vec<bool>,
+}
+
+impl Visibility {
+	/// Construct an empty visibility set for the specified area.
+	pub fn new(area: Rect) -> Visibility {
+		Visibility{
+			area: area,
+			visible: vec![false; (area.size.width * area.size.height) as usize],
+		}
+	}
+
+	/// Get the area the visibility was computed in.
+	pub fn area(&self) -> Rect {
+		self.area
+	}
+
+	/// Check whether the point is visible, points outside the area never being visible.
+	pub fn is_visible(&self, point: Point) -> bool {
+		self.index(point).map(|idx| self.visible[idx]).unwrap_or(false)
+	}
+
+	/// Mark the point visible, ignoring points outside the area.
+	pub fn set_visible(&mut self, point: Point) {
+		if let Some(idx) = self.index(point) {
+			self.visible[idx] = true;
+		}
+	}
+
+	/// Iterate over the visible points, row by row.
+	pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+		self.area.points().filter(move |&point| self.is_visible(point))
+	}
+
+	fn index(&self, point: Point) -> Option<usize> {
+		if self.area.contains(point) {
+			Some(((point.y - self.area.top_left.y) * self.area.size.width + (point.x - self.area.top_left.x)) as usize)
+		} else {
+			None
+		}
+	}
+}
+
+
+/// Compute the field of view from the origin with [recursive shadowcasting](http://www.roguebasin.com/index.php/FOV_using_recursive_shadowcasting).
+///
+/// Only points at most `radius` away (euclidean) and within `bounds` can be visible.
+pub fn shadowcast<F: Fn(Point) -> bool>(origin: Point, radius: i32, bounds: Rect, is_opaque: F) -> Visibility {
+	let mut visibility = Visibility::new(bounds);
+	visibility.set_visible(origin);
+
+	let mut shadowcast = Shadowcast{
+		visibility: &mut visibility,
+		opaque: |point: Point| !bounds.contains(point) || is_opaque(point),
+		origin: origin,
+		radius: radius,
+	};
+	for &octant in OCTANTS.iter() {
+		shadowcast.cast(1, 1.0, 0.0, octant);
+	}
+	visibility
+}
+
+/// Compute a permissive field of view from the origin.
+///
+/// A point is visible if any of the digital lines to it, of which there are as many as the longer axis of the distance,
+/// passes through transparent points only.
+/// This sees more around corners and pillars than [`shadowcast()`](fn.shadowcast.html).
+///
+/// Only points at most `radius` away (euclidean) and within `bounds` can be visible.
+///
+/// # Examples
+///
+/// ```
+/// # use bear_lib_terminal::fov;
+/// # use bear_lib_terminal::geometry::{Point, Rect};
+/// let pillar = |point: Point| point == Point::new(2, 1);
+/// let bounds = Rect::from_values(0, 0, 5, 3);
+/// let visible = fov::permissive(Point::new(0, 1), 10, bounds, &pillar);
+/// assert!(!visible.is_visible(Point::new(4, 1)));
+/// assert!(visible.is_visible(Point::new(4, 2)));
+/// ```
+pub fn permissive<F: Fn(Point) -> bool>(origin: Point, radius: i32, bounds: Rect, is_opaque: F) -> Visibility {
+	let mut visibility = Visibility::new(bounds);
+	let opaque = |point: Point| !bounds.contains(point) || is_opaque(point);
+	for point in bounds.points() {
+		let in_sight = || digital_lines(origin, point).any(|mut line| line.all(|point| !opaque(point)));
+		if in_radius(point.x - origin.x, point.y - origin.y, radius) && in_sight() {
+			visibility.set_visible(point);
+		}
+	}
+	visibility
+}
+
+/// Check whether there's a clear [Bresenham line](../draw/fn.line_points.html) between the two points in either direction,
+/// the endpoints themselves not counting.
+///
+/// Points outside `bounds` count as opaque.
+pub fn line_of_sight<F: Fn(Point) -> bool>(from: Point, to: Point, bounds: Rect, is_opaque: F) -> bool {
+	let clear = |from: Point, to: Point| draw::line_points(from, to).filter(|&point| point != from && point != to)
+	                                                                  .all(|point| bounds.contains(point) && !is_opaque(point));
+	clear(from, to) || clear(to, from)
+}
+
+
+/// The transformations of the first octant into all eight, as `(xx, xy, yx, yy)`.
+static OCTANTS: [(i32, i32, i32, i32); 8] = [(1, 0, 0, 1), (0, 1, 1, 0), (0, -1, 1, 0), (-1, 0, 0, 1),
+                                              (-1, 0, 0, -1), (0, -1, -1, 0), (0, 1, -1, 0), (1, 0, 0, -1)];
+
+/// The state shared by all octants and recursion levels of a shadowcast.
+struct Shadowcast<'v, F> {
+	visibility: &'v mut Visibility,
+	opaque: F,
+	origin: Point,
+	radius: i32,
+}
+
+impl<'v, F: Fn(Point) -> bool> Shadowcast<'v, F> {
+	/// Light the octant's rows starting at `row`, between the `start` and `end` slopes.
+	fn cast(&mut self, row: i32, mut start: f32, end: f32, octant: (i32, i32, i32, i32)) {
+		if start < end {
+			return;
+		}
+
+		let (xx, xy, yx, yy) = octant;
+		let mut next_start = start;
+		for distance in row..self.radius + 1 {
+			let mut blocked = false;
+			for dx in -distance..1 {
+				let dy = -distance;
+				let left_slope = (dx as f32 - 0.5) / (dy as f32 + 0.5);
+				let right_slope = (dx as f32 + 0.5) / (dy as f32 - 0.5);
+				if start < right_slope {
+					continue;
+				} else if end > left_slope {
+					break;
+				}
+
+				let point = Point::new(self.origin.x + dx * xx + dy * xy, self.origin.y + dx * yx + dy * yy);
+				if in_radius(dx, dy, self.radius) {
+					self.visibility.set_visible(point);
+				}
+
+				if blocked {
+					if (self.opaque)(point) {
+						next_start = right_slope;
+					} else {
+						blocked = false;
+						start = next_start;
+					}
+				} else if (self.opaque)(point) && distance < self.radius {
+					blocked = true;
+					self.cast(distance + 1, start, left_slope, octant);
+					next_start = right_slope;
+				}
+			}
+
+			if blocked {
+				break;
+			}
+		}
+	}
+}
+
+/// Iterate over the digital lines between the two points, each yielding its points strictly between them.
+fn digital_lines(from: Point, to: Point) -> impl Iterator<Item = impl Iterator<Item = Point>> {
+	let (dx, dy) = (to.x - from.x, to.y - from.y);
+	let (major, minor) = (dx.abs().max(dy.abs()), dx.abs().min(dy.abs()));
+	let x_major = dx.abs() >= dy.abs();
+	(0..major.max(1)).map(move |error| (1..major).map(move |step| {
+		let (along, across) = (step, (step * minor + error) / major);
+		let (x, y) = if x_major {(along, across)} else {(across, along)};
+		Point::new(from.x + x * dx.signum(), from.y + y * dy.signum())
+	}))
+}
+
+fn in_radius(dx: i32, dy: i32, radius: i32) -> bool {
+	dx * dx + dy * dy <= radius * radius
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::{line_of_sight, permissive, shadowcast, Visibility};
+	use geometry::{Point, Rect};
+
+
+	static MAP: [&str; 5] = ["#######",
+	                         "#.....#",
+	                         "#..#..#",
+	                         "#.....#",
+	                         "#######"];
+
+	fn is_opaque(point: Point) -> bool {
+		MAP[point.y as usize].as_bytes()[point.x as usize] == b'#'
+	}
+
+	fn bounds() -> Rect {
+		Rect::from_values(0, 0, 7, 5)
+	}
+
+	fn open(_: Point) -> bool {
+		false
+	}
+
+
+	#[test]
+	fn zero_radius_sees_origin() {
+		for visible in &[shadowcast(Point::new(2, 2), 0, bounds(), is_opaque), permissive(Point::new(2, 2), 0, bounds(), is_opaque)] {
+			assert_eq!(visible.points().collect::<Vec<_>>(), vec![Point::new(2, 2)]);
+		}
+	}
+
+	#[test]
+	fn radius_is_euclidean() {
+		let area = Rect::from_values(-5, -5, 11, 11);
+		let expected = area.points().filter(|point| point.x * point.x + point.y * point.y <= 9).count();
+		assert_eq!(expected, 29);
+		assert_eq!(shadowcast(Point::new(0, 0), 3, area, open).points().count(), expected);
+		assert_eq!(permissive(Point::new(0, 0), 3, area, open).points().count(), expected);
+	}
+
+	#[test]
+	fn walls_are_lit() {
+		let visible = shadowcast(Point::new(1, 1), 10, bounds(), is_opaque);
+		assert!(visible.is_visible(Point::new(0, 0)));
+		assert!(visible.is_visible(Point::new(6, 1)));
+		assert!(visible.is_visible(Point::new(3, 2)));
+		assert!(!visible.is_visible(Point::new(5, 3)));
+	}
+
+	#[test]
+	fn bounds_clip() {
+		let area = Rect::from_values(2, 2, 3, 3);
+		let visible = shadowcast(Point::new(2, 2), 10, area, open);
+		assert_eq!(visible.area(), area);
+		assert_eq!(visible.points().count(), 9);
+		assert!(!visible.is_visible(Point::new(1, 1)));
+		assert!(!permissive(Point::new(2, 2), 10, area, open).is_visible(Point::new(5, 2)));
+	}
+
+	#[test]
+	fn permissive_sees_around_pillars() {
+		assert!(!shadowcast(Point::new(2, 2), 10, bounds(), is_opaque).is_visible(Point::new(5, 1)));
+		assert!(permissive(Point::new(2, 2), 10, bounds(), is_opaque).is_visible(Point::new(5, 1)));
+		assert!(!permissive(Point::new(2, 2), 10, bounds(), is_opaque).is_visible(Point::new(4, 2)));
+	}
+
+	#[test]
+	fn line_of_sight_edges() {
+		assert!(line_of_sight(Point::new(3, 2), Point::new(3, 2), bounds(), is_opaque));
+		assert!(line_of_sight(Point::new(0, 0), Point::new(1, 1), bounds(), is_opaque));
+		assert!(line_of_sight(Point::new(1, 3), Point::new(5, 3), bounds(), is_opaque));
+		assert!(!line_of_sight(Point::new(1, 2), Point::new(5, 2), bounds(), is_opaque));
+		assert!(!line_of_sight(Point::new(0, 0), Point::new(4, 0), Rect::from_values(0, 0, 1, 1), open));
+	}
+
+	#[test]
+	fn visibility_points_row_by_row() {
+		let mut visibility = Visibility::new(Rect::from_values(1, 1, 3, 2));
+		visibility.set_visible(Point::new(3, 2));
+		visibility.set_visible(Point::new(1, 1));
+		visibility.set_visible(Point::new(0, 0));
+		assert_eq!(visibility.points().collect::<Vec<_>>(), vec![Point::new(1, 1), Point::new(3, 2)]);
+	}
+}
@@ -1,10 +1,26 @@
 //! Helper `struct`s for abstracting on-screen geometry.
 
 
+use std::cmp;
 use std::fmt;
+use std::ops::{Add, Neg, Sub};
+use std::str::FromStr;
 
 
 /// Represents a single on-screen point/coordinate pair.
+///
+/// Points may be negative, e.g. off-screen or relative to a scrolled view.
+///
+/// # Examples
+///
+/// ```
+/// # use bear_lib_terminal::geometry::{Offset, Point, Size};
+/// let player = Point::new(3, 4);
+/// assert_eq!(player + Offset::new(-1, 1), Point::new(2, 5));
+/// assert_eq!(player - Offset::new(5, 0), Point::new(-2, 4));
+/// assert_eq!(Point::new(10, 2) - player, Offset::new(7, -2));
+/// assert_eq!(player + Size::new(2, 2), Point::new(5, 6));
+/// ```
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Point {
 	pub x: i32,
@@ -12,11 +28,8 @@ pub struct Point {
 }
 
 impl Point {
-	/// Creates a new point on the specified non-negative coordinates
+	/// Creates a new point on the specified coordinates.
 	pub fn new(x: i32, y: i32) -> Point {
-		assert!(x >= 0);
-		assert!(y >= 0);
-
 		Point{
 			x: x,
 			y: y
@@ -24,6 +37,91 @@ impl Point {
 	}
 }
 
+impl Add<Offset> for Point {
+	type Output = Point;
+
+	fn add(self, offset: Offset) -> Point {
+		Point::new(self.x + offset.x, self.y + offset.y)
+	}
+}
+
+impl Sub<Offset> for Point {
+	type Output = Point;
+
+	fn sub(self, offset: Offset) -> Point {
+		Point::new(self.x - offset.x, self.y - offset.y)
+	}
+}
+
+/// The offset from the other point to this one.
+impl Sub for Point {
+	type Output = Offset;
+
+	fn sub(self, other: Point) -> Offset {
+		Offset::new(self.x - other.x, self.y - other.y)
+	}
+}
+
+/// The point `size` away towards the bottom-right.
+impl Add<Size> for Point {
+	type Output = Point;
+
+	fn add(self, size: Size) -> Point {
+		Point::new(self.x + size.width, self.y + size.height)
+	}
+}
+
+
+/// A signed delta between two [`Point`](struct.Point.html)s.
+///
+/// # Examples
+///
+/// ```
+/// # use bear_lib_terminal::geometry::Offset;
+/// assert_eq!(Offset::new(1, -2) + Offset::new(3, 3), Offset::new(4, 1));
+/// assert_eq!(Offset::new(1, -2) - Offset::new(3, 3), Offset::new(-2, -5));
+/// assert_eq!(-Offset::new(1, -2), Offset::new(-1, 2));
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Offset {
+	pub x: i32,
+	pub y: i32,
+}
+
+impl Offset {
+	/// Creates a new offset by the specified amounts.
+	pub fn new(x: i32, y: i32) -> Offset {
+		Offset{
+			x: x,
+			y: y,
+		}
+	}
+}
+
+impl Add for Offset {
+	type Output = Offset;
+
+	fn add(self, other: Offset) -> Offset {
+		Offset::new(self.x + other.x, self.y + other.y)
+	}
+}
+
+impl Sub for Offset {
+	type Output = Offset;
+
+	fn sub(self, other: Offset) -> Offset {
+		Offset::new(self.x - other.x, self.y - other.y)
+	}
+}
+
+impl Neg for Offset {
+	type Output = Offset;
+
+	fn neg(self) -> Offset {
+		Offset::new(-self.x, -self.y)
+	}
+}
+
 
 /// A 2D size representation.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
@@ -51,8 +149,48 @@ impl fmt::Display for Size {
 	}
 }
 
+impl Add for Size {
+	type Output = Size;
+
+	fn add(self, other: Size) -> Size {
+		Size::new(self.width + other.width, self.height + other.height)
+	}
+}
+
+/// Panics if either dimension would become negative.
+impl Sub for Size {
+	type Output = Size;
+
+	fn sub(self, other: Size) -> Size {
+		Size::new(self.width - other.width, self.height - other.height)
+	}
+}
+
 
 /// A rectangle, described by its four corners and a size.
+///
+/// The right and bottom edges are exclusive, i.e. `bottom_right` is just outside the `Rect`angle.
+///
+/// # Examples
+///
+/// ```
+/// # use bear_lib_terminal::geometry::{Point, Rect};
+/// let map = Rect::from_values(0, 0, 10, 6);
+/// let (sidebar, main) = map.split_horizontal(3);
+/// assert_eq!(sidebar, Rect::from_values(0, 0, 3, 6));
+/// assert_eq!(main, Rect::from_values(3, 0, 7, 6));
+///
+/// assert!(main.contains(Point::new(9, 5)));
+/// assert!(!main.contains(Point::new(10, 5)));
+/// assert_eq!(main.center(), Point::new(6, 3));
+///
+/// let room = Rect::from_values(8, 4, 5, 5);
+/// assert_eq!(map.intersection(room), Some(Rect::from_values(8, 4, 2, 2)));
+/// assert_eq!(map.union(room), Rect::from_values(0, 0, 13, 9));
+/// assert_eq!(room.inflate(-1), Rect::from_values(9, 5, 3, 3));
+/// assert_eq!(Rect::from_values(1, 1, 2, 2).points().collect::<Vec<_>>(),
+///            vec![Point::new(1, 1), Point::new(2, 1), Point::new(1, 2), Point::new(2, 2)]);
+/// ```
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Rect {
 	/// The top-left corner.
@@ -144,4 +282,212 @@ impl Rect {
 		let bottom_right = Point::new(bottom_right_x, bottom_right_y);
 		Rect::from_points(top_left, bottom_right)
 	}
+
+	/// Check whether the point lies within the `Rect`angle, the right and bottom edges being exclusive.
+	pub fn contains(&self, point: Point) -> bool {
+		point.x >= self.top_left.x && point.x < self.bottom_right.x && point.y >= self.top_left.y && point.y < self.bottom_right.y
+	}
+
+	/// Get the overlapping part of the two `Rect`angles, or `None` if they don't overlap.
+	pub fn intersection(&self, other: Rect) -> Option<Rect> {
+		let top_left = Point::new(cmp::max(self.top_left.x, other.top_left.x), cmp::max(self.top_left.y, other.top_left.y));
+		let bottom_right = Point::new(cmp::min(self.bottom_right.x, other.bottom_right.x), cmp::min(self.bottom_right.y, other.bottom_right.y));
+		if top_left.x < bottom_right.x && top_left.y < bottom_right.y {
+			Some(Rect::from_points(top_left, bottom_right))
+		} else {
+			None
+		}
+	}
+
+	/// Get the smallest `Rect`angle containing both.
+	pub fn union(&self, other: Rect) -> Rect {
+		let top_left = Point::new(cmp::min(self.top_left.x, other.top_left.x), cmp::min(self.top_left.y, other.top_left.y));
+		let bottom_right = Point::new(cmp::max(self.bottom_right.x, other.bottom_right.x), cmp::max(self.bottom_right.y, other.bottom_right.y));
+		Rect::from_points(top_left, bottom_right)
+	}
+
+	/// Grow each side by the specified amount, or shrink it if negative.
+	///
+	/// Shrinking past the center collapses the `Rect`angle to an empty one there.
+	pub fn inflate(&self, by: i32) -> Rect {
+		let center = self.center();
+		let top_left = Point::new(cmp::min(self.top_left.x - by, center.x), cmp::min(self.top_left.y - by, center.y));
+		let bottom_right = Point::new(cmp::max(self.bottom_right.x + by, top_left.x), cmp::max(self.bottom_right.y + by, top_left.y));
+		Rect::from_points(top_left, bottom_right)
+	}
+
+	/// Get the center point, rounded towards the top-left.
+	pub fn center(&self) -> Point {
+		Point::new(self.top_left.x + self.size.width / 2, self.top_left.y + self.size.height / 2)
+	}
+
+	/// Iterate over all points within the `Rect`angle, row by row.
+	pub fn points(&self) -> Points {
+		Points{
+			area: *self,
+			next: self.top_left,
+		}
+	}
+
+	/// Split into the left part `at` columns wide and the right part with the rest, `at` being clamped to the width.
+	pub fn split_horizontal(&self, at: i32) -> (Rect, Rect) {
+		let at = cmp::max(0, cmp::min(at, self.size.width));
+		(Rect::from_size(self.top_left, Size::new(at, self.size.height)),
+		 Rect::from_size(Point::new(self.top_left.x + at, self.top_left.y), Size::new(self.size.width - at, self.size.height)))
+	}
+
+	/// Split into the top part `at` rows high and the bottom part with the rest, `at` being clamped to the height.
+	pub fn split_vertical(&self, at: i32) -> (Rect, Rect) {
+		let at = cmp::max(0, cmp::min(at, self.size.height));
+		(Rect::from_size(self.top_left, Size::new(self.size.width, at)),
+		 Rect::from_size(Point::new(self.top_left.x, self.top_left.y + at), Size::new(self.size.width, self.size.height - at)))
+	}
+}
+
+/// Moves the whole `Rect`angle.
+impl Add<Offset> for Rect {
+	type Output = Rect;
+
+	fn add(self, offset: Offset) -> Rect {
+		Rect::from_size(self.top_left + offset, self.size)
+	}
+}
+
+/// Moves the whole `Rect`angle.
+impl Sub<Offset> for Rect {
+	type Output = Rect;
+
+	fn sub(self, offset: Offset) -> Rect {
+		Rect::from_size(self.top_left - offset, self.size)
+	}
+}
+
+
+/// Iterator over the points within a [`Rect`](struct.Rect.html), in row-major order.
+///
+/// Obtained from [`Rect::points()`](struct.Rect.html#method.points).
+#[derive(Clone, Debug)]
+pub struct Points {
+	area: Rect,
+	next: Point,
+}
+
+impl Iterator for Points {
+	type Item = Point;
+
+	fn next(&mut self) -> Option<Point> {
+		if self.area.size.width == 0 || self.next.y >= self.area.bottom_right.y {
+			return None;
+		}
+
+		let point = self.next;
+		self.next.x += 1;
+		if self.next.x >= self.area.bottom_right.x {
+			self.next = Point::new(self.area.top_left.x, self.next.y + 1);
+		}
+		Some(point)
+	}
+}
+
+
+/// Horizontal text alignment, as used by the [`align` markup tag](http://foo.wyrd.name/en:bearlibterminal:reference#print).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum HorizontalAlignment {
+	/// Whatever BLT defaults to, i.e. left.
+	Default,
+	Left,
+	Center,
+	Right,
+}
+
+/// Vertical text alignment, as used by the [`align` markup tag](http://foo.wyrd.name/en:bearlibterminal:reference#print).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum VerticalAlignment {
+	/// Whatever BLT defaults to, i.e. top.
+	Default,
+	Top,
+	Middle,
+	Bottom,
+}
+
+/// Alignment of text within a bounding box.
+///
+/// Parses from and formats to the `align` markup tag's value format, i.e. one or both of the horizontal (`left`, `center`, `right`)
+/// and vertical (`top`, `middle`, `bottom`) alignments joined with a `-`.
+///
+/// # Examples
+///
+/// ```
+/// # use bear_lib_terminal::geometry::{Alignment, HorizontalAlignment, VerticalAlignment};
+/// let align: Alignment = "bottom-center".parse().unwrap();
+/// assert_eq!(align, Alignment::new(HorizontalAlignment::Center, VerticalAlignment::Bottom));
+/// assert_eq!(align.to_string(), "bottom-center");
+/// assert_eq!("right".parse::<Alignment>().unwrap().to_string(), "right");
+/// assert!("sideways".parse::<Alignment>().is_err());
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Alignment {
+	pub horizontal: HorizontalAlignment,
+	pub vertical: VerticalAlignment,
+}
+
+impl Alignment {
+	/// Construct an alignment from its horizontal and vertical parts.
+	pub fn new(horizontal: HorizontalAlignment, vertical: VerticalAlignment) -> Alignment {
+		Alignment{
+			horizontal: horizontal,
+			vertical: vertical,
+		}
+	}
+}
+
+/// BLT's default alignment, i.e. top-left.
+impl Default for Alignment {
+	fn default() -> Alignment {
+		Alignment::new(HorizontalAlignment::Default, VerticalAlignment::Default)
+	}
+}
+
+impl fmt::Display for Alignment {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		let vertical = match self.vertical {
+			VerticalAlignment::Default => None,
+			VerticalAlignment::Top     => Some("top"),
+			VerticalAlignment::Middle  => Some("middle"),
+			VerticalAlignment::Bottom  => Some("bottom"),
+		};
+		let horizontal = match self.horizontal {
+			HorizontalAlignment::Default => None,
+			HorizontalAlignment::Left    => Some("left"),
+			HorizontalAlignment::Center  => Some("center"),
+			HorizontalAlignment::Right   => Some("right"),
+		};
+
+		match (vertical, horizontal) {
+			(Some(vertical), Some(horizontal)) => write!(formatter, "{}-{}", vertical, horizontal),
+			(Some(align), None) | (None, Some(align)) => formatter.write_str(align),
+			(None, None) => formatter.write_str("default"),
+		}
+	}
+}
+
+impl FromStr for Alignment {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Alignment, String> {
+		let mut align = Alignment::default();
+		for part in s.trim().split('-') {
+			match part {
+				"left"    => align.horizontal = HorizontalAlignment::Left,
+				"center"  => align.horizontal = HorizontalAlignment::Center,
+				"right"   => align.horizontal = HorizontalAlignment::Right,
+				"top"     => align.vertical = VerticalAlignment::Top,
+				"middle"  => align.vertical = VerticalAlignment::Middle,
+				"bottom"  => align.vertical = VerticalAlignment::Bottom,
+				"default" => (),
+				_         => return Err(format!("unknown alignment \"{}\"", part)),
+			}
+		}
+		Ok(align)
+	}
 }
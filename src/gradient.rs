@@ -0,0 +1,183 @@
+//! Multi-stop colour ramps, sampled at points along them or spread across an area via [`put_ext()`](../terminal/fn.put_ext.html) corners.
+//!
+//! # Examples
+//!
+//! ```
+//! use bear_lib_terminal::{Color, Gradient};
+//! use bear_lib_terminal::terminal;
+//! use bear_lib_terminal::terminal::backend::Headless;
+//! use bear_lib_terminal::geometry::{Point, Rect};
+//!
+//! let health = Gradient::new(Color::RED, Color::GREEN).stop(0.5, Color::YELLOW);
+//! terminal::open_with(Headless::new(), "gradient example", 10, 1).unwrap();
+//! health.fill(Rect::from_values(0, 0, 10, 1), '█');
+//! assert_eq!(terminal::pick_foreground_color(Point::new(5, 0), 0), Ok(Color::YELLOW));
+//! terminal::close();
+//! ```
+
+
+use colors::Color;
+use geometry::{Point, Rect};
+use terminal::{self, Corners};
+
+
+/// A multi-stop colour ramp, for health bars, `put_ext()` corner gradients and the like.
+///
+/// Stops are positioned in `[0, 1]`, before the first and after the last stop the gradient is flat.
+///
+/// # Examples
+///
+/// ```
+/// use bear_lib_terminal::{Color, Gradient, GradientDirection, Interpolation};
+/// use bear_lib_terminal::geometry::{Point, Rect};
+///
+/// let health = Gradient::new(Color::RED, Color::GREEN).stop(0.5, Color::YELLOW);
+/// assert_eq!(health.sample(3), vec![Color::RED, Color::YELLOW, Color::GREEN]);
+/// assert_eq!(health.at(0.25), Color::from_rgb(0xFF, 0x80, 0x00));
+///
+/// let hues = Gradient::new(Color::RED, Color::BLUE).interpolation(Interpolation::Hsv);
+/// assert_eq!(hues.at(0.5), Color::FUCHSIA);
+///
+/// let fade = Gradient::new(Color::BLACK, Color::WHITE).direction(GradientDirection::Horizontal);
+/// let area = Rect::from_values(0, 0, 4, 1);
+/// assert_eq!(fade.corners(area, Point::new(1, 0)), [Color::from_rgb(0x40, 0x40, 0x40), Color::from_rgb(0x40, 0x40, 0x40),
+///                                                   Color::GREY,                       Color::GREY]);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gradient {
+	stops: Vec<(f32, Color)>,
+	interpolation: Interpolation,
+	direction: GradientDirection,
+}
+
+/// The colour space a [`Gradient`](struct.Gradient.html) interpolates its stops in.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Interpolation {
+	/// Channel-wise, like [`Color::lerp()`](struct.Color.html#method.lerp).
+	Rgb,
+	/// Along the shorter way around the hue wheel, keeping saturated colours saturated.
+	Hsv,
+}
+
+/// Which way a [`Gradient`](struct.Gradient.html) runs across a `Rect`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum GradientDirection {
+	/// From the left edge to the right one.
+	Horizontal,
+	/// From the top edge to the bottom one.
+	Vertical,
+	/// From the top-left corner to the bottom-right one.
+	Diagonal,
+}
+
+impl Gradient {
+	/// Construct a horizontal, RGB-interpolated gradient from one colour to the other.
+	pub fn new(from: Color, to: Color) -> Gradient {
+		Gradient{
+			stops: vec![(0.0, from), (1.0, to)],
+			interpolation: Interpolation::Rgb,
+			direction: GradientDirection::Horizontal,
+		}
+	}
+
+	/// Construct a gradient with the specified colours spaced evenly.
+	///
+	/// Panics if `colors` is empty.
+	pub fn from_colors(colors: &[Color]) -> Gradient {
+		assert!(!colors.is_empty());
+
+		let last = (colors.len() - 1).max(1) as f32;
+		Gradient{
+			stops: colors.iter().enumerate().map(|(idx, &color)| (idx as f32 / last, color)).collect(),
+			interpolation: Interpolation::Rgb,
+			direction: GradientDirection::Horizontal,
+		}
+	}
+
+	/// Add a stop at the specified position, clamped to `[0, 1]`, after any stops already there.
+	pub fn stop(mut self, position: f32, color: Color) -> Self {
+		let position = position.clamp(0.0, 1.0);
+		let idx = self.stops.iter().position(|&(at, _)| at > position).unwrap_or(self.stops.len());
+		self.stops.insert(idx, (position, color));
+		self
+	}
+
+	/// The colour space to interpolate between stops in.
+	///
+	/// Default: [`Interpolation::Rgb`](enum.Interpolation.html#variant.Rgb).
+	pub fn interpolation(mut self, interpolation: Interpolation) -> Self {self.interpolation = interpolation; self}
+
+	/// Which way the gradient runs across the area [`fill()`](#method.fill)ed.
+	///
+	/// Default: [`GradientDirection::Horizontal`](enum.GradientDirection.html#variant.Horizontal).
+	pub fn direction    (mut self, direction: GradientDirection) -> Self {self.direction     = direction    ; self}
+
+	/// Get the colour at the specified position, clamped to `[0, 1]`.
+	pub fn at(&self, position: f32) -> Color {
+		let position = position.clamp(0.0, 1.0);
+		let after = self.stops.iter().position(|&(at, _)| at > position).unwrap_or(self.stops.len());
+		if after == 0 {
+			return self.stops[0].1;
+		} else if after == self.stops.len() {
+			return self.stops[after - 1].1;
+		}
+
+		let (from_at, from) = self.stops[after - 1];
+		let (to_at, to) = self.stops[after];
+		let amount = (position - from_at) / (to_at - from_at);
+		match self.interpolation {
+			Interpolation::Rgb => from.lerp(to, amount),
+			Interpolation::Hsv => lerp_hsv(from, to, amount),
+		}
+	}
+
+	/// Get `count` evenly-spaced colours, including both ends.
+	pub fn sample(&self, count: usize) -> Vec<Color> {
+		let last = (count.max(2) - 1) as f32;
+		(0..count).map(|idx| self.at(idx as f32 / last)).collect()
+	}
+
+	/// Get the corner colours of the specified cell, as if the gradient were stretched across the whole `area`.
+	///
+	/// The corners are in [`put_ext()`](terminal/fn.put_ext.html) order, i.e. top-left, bottom-left, bottom-right, top-right.
+	pub fn corners(&self, area: Rect, cell: Point) -> [Color; 4] {
+		let x = |offset: i32| (cell.x + offset - area.top_left.x) as f32 / area.size.width.max(1) as f32;
+		let y = |offset: i32| (cell.y + offset - area.top_left.y) as f32 / area.size.height.max(1) as f32;
+		let corner = |dx: i32, dy: i32| self.at(match self.direction {
+			GradientDirection::Horizontal => x(dx),
+			GradientDirection::Vertical   => y(dy),
+			GradientDirection::Diagonal   => (x(dx) + y(dy)) / 2.0,
+		});
+
+		[corner(0, 0), corner(0, 1), corner(1, 1), corner(1, 0)]
+	}
+
+	/// Fill the `area` with the specified character, smoothly colouring it with [`put_ext()`](terminal/fn.put_ext.html).
+	pub fn fill(&self, area: Rect, cell: char) {
+		for y in area.top_left.y..area.bottom_right.y {
+			for x in area.top_left.x..area.bottom_right.x {
+				let point = Point::new(x, y);
+				terminal::put_ext(point, Point::new(0, 0), cell, Corners::Each(self.corners(area, point)));
+			}
+		}
+	}
+}
+
+
+fn lerp_hsv(from: Color, to: Color, amount: f32) -> Color {
+	let (from_hue, from_saturation, from_value) = from.to_hsv();
+	let (to_hue, to_saturation, to_value) = to.to_hsv();
+
+	let mut hue_delta = to_hue - from_hue;
+	if hue_delta > 180.0 {
+		hue_delta -= 360.0;
+	} else if hue_delta < -180.0 {
+		hue_delta += 360.0;
+	}
+
+	let mut color = Color::from_hsv(from_hue + hue_delta * amount,
+	                                from_saturation + (to_saturation - from_saturation) * amount,
+	                                from_value + (to_value - from_value) * amount);
+	color.alpha = from.lerp(to, amount).alpha;
+	color
+}
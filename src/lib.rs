@@ -7,7 +7,7 @@
 //! ```ignore
 //! use bear_lib_terminal::terminal;
 //!
-//! terminal::open("Test", 80, 30);
+//! terminal::open("Test", 80, 30).unwrap();
 //! terminal::print_xy(0, 0, "[color=red]asdf[bkcolor=blue]asdf");
 //! terminal::refresh();
 //! let _ = terminal::wait_event();
@@ -18,7 +18,15 @@
 extern crate bear_lib_terminal_sys;
 
 mod colors;
+mod gradient;
+mod viewport;
+pub mod draw;
+pub mod fov;
 pub mod geometry;
+pub mod markup;
+pub mod path;
 pub mod terminal;
 
-pub use colors::Color;
+pub use colors::{BlendMode, Color};
+pub use gradient::{Gradient, GradientDirection, Interpolation};
+pub use viewport::Viewport;
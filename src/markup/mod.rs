@@ -0,0 +1,210 @@
+//! Parsing and generating the markup accepted by [`terminal::print()`](../terminal/fn.print.html) and
+//! [`terminal::measure()`](../terminal/fn.measure.html).
+//!
+//! For the markup spec see the docs for the [`terminal_print()` C API function](http://foo.wyrd.name/en:bearlibterminal:reference#print).
+//!
+//! # Examples
+//!
+//! ```
+//! use bear_lib_terminal::markup::{self, Element};
+//!
+//! let parsed = markup::parse("[color=red]HP[/color]: [[12]]").unwrap();
+//! assert_eq!(parsed.elements(), vec![&Element::Color("red".to_string()),
+//!                                    &Element::Text("HP".to_string()),
+//!                                    &Element::ColorEnd,
+//!                                    &Element::Text(": [12]".to_string())]);
+//! assert_eq!(parsed.nodes[1].span, 11..13);
+//! assert_eq!(parsed.to_string(), "[color=red]HP[/color]: [[12]]");
+//!
+//! let error = markup::parse("[colour=red]HP").unwrap_err();
+//! assert_eq!(error.span, 0..12);
+//! ```
+
+
+mod parse;
+mod styled;
+
+use std::fmt;
+use std::ops::Range;
+use std::error::Error;
+use std::str::FromStr;
+use geometry::{Alignment, Point, Size};
+
+pub use self::parse::parse;
+pub use self::styled::{Styled, text};
+
+
+/// A parsed markup string.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Markup {
+	/// The string's elements, in order of appearance.
+	pub nodes: Vec<Node>,
+}
+
+/// A single element of a markup string along with its location.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Node {
+	pub element: Element,
+	/// Byte range of the element in the source string, or `0..0` for nodes not originating from a parsed string.
+	pub span: Range<usize>,
+}
+
+/// A markup element.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Element {
+	/// Literal, unescaped, text.
+	Text(String),
+	/// `[color=value]`.
+	Color(String),
+	/// `[/color]`.
+	ColorEnd,
+	/// `[bkcolor=value]`.
+	Bkcolor(String),
+	/// `[/bkcolor]`.
+	BkcolorEnd,
+	/// `[font=name]`.
+	Font(String),
+	/// `[/font]`.
+	FontEnd,
+	/// `[offset=x,y]`, in pixels.
+	Offset(Point),
+	/// `[/offset]`.
+	OffsetEnd,
+	/// `[+]`, composes the next character onto the previous one.
+	Combine,
+	/// `[U+XXXX]` or `[0xXXXX]`.
+	Codepoint(char),
+	/// `[bbox=W]` or `[bbox=WxH]`, the height is `0` if unspecified.
+	Bbox(Size),
+	/// `[align=alignment]`.
+	Align(Alignment),
+}
+
+/// An error encountered while parsing a markup string.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ParseError {
+	pub kind: ParseErrorKind,
+	/// Byte range of the offending part of the source string.
+	pub span: Range<usize>,
+}
+
+/// All the ways parsing a markup string can fail.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ParseErrorKind {
+	/// A `[` without a matching `]`.
+	UnterminatedTag,
+	/// A tag not supported by BLT, with its name.
+	UnknownTag(String),
+	/// A tag requiring a value was specified without one, with the tag's name.
+	MissingValue(String),
+	/// A tag's value couldn't be parsed.
+	InvalidValue{
+		tag: String,
+		value: String,
+	},
+	/// A `U+`/`0x` tag didn't specify a valid Unicode scalar value, with the offending value.
+	InvalidCodepoint(String),
+}
+
+
+impl Markup {
+	/// Construct a markup string from the specified elements.
+	pub fn new(elements: Vec<Element>) -> Markup {
+		Markup{
+			nodes: elements.into_iter().map(|element| Node{
+				element: element,
+				span: 0..0,
+			}).collect(),
+		}
+	}
+
+	/// Get all elements without their locations.
+	pub fn elements(&self) -> Vec<&Element> {
+		self.nodes.iter().map(|node| &node.element).collect()
+	}
+
+	/// Get the plain text, with all the tags stripped and codepoints resolved.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use bear_lib_terminal::markup;
+	/// assert_eq!(markup::parse("[color=red][[[U+263A]]][/color]").unwrap().text(), "[\u{263A}]");
+	/// ```
+	pub fn text(&self) -> String {
+		let mut text = String::new();
+		for node in &self.nodes {
+			match node.element {
+				Element::Text(ref value)      => text.push_str(value),
+				Element::Codepoint(codepoint) => text.push(codepoint),
+				_                             => (),
+			}
+		}
+		text
+	}
+}
+
+impl fmt::Display for Markup {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		for node in &self.nodes {
+			write!(formatter, "{}", node.element)?;
+		}
+		Ok(())
+	}
+}
+
+impl FromStr for Markup {
+	type Err = ParseError;
+
+	fn from_str(s: &str) -> Result<Markup, ParseError> {
+		parse(s)
+	}
+}
+
+impl fmt::Display for Element {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			&Element::Text(ref value)              => formatter.write_str(&escape(value)),
+			&Element::Color(ref value)             => write!(formatter, "[color={}]", value),
+			&Element::ColorEnd                     => formatter.write_str("[/color]"),
+			&Element::Bkcolor(ref value)           => write!(formatter, "[bkcolor={}]", value),
+			&Element::BkcolorEnd                   => formatter.write_str("[/bkcolor]"),
+			&Element::Font(ref name)               => write!(formatter, "[font={}]", name),
+			&Element::FontEnd                      => formatter.write_str("[/font]"),
+			&Element::Offset(offset)               => write!(formatter, "[offset={},{}]", offset.x, offset.y),
+			&Element::OffsetEnd                    => formatter.write_str("[/offset]"),
+			&Element::Combine                      => formatter.write_str("[+]"),
+			&Element::Codepoint(codepoint)         => write!(formatter, "[U+{:04X}]", codepoint as u32),
+			&Element::Bbox(Size{width, height: 0}) => write!(formatter, "[bbox={}]", width),
+			&Element::Bbox(size)                   => write!(formatter, "[bbox={}]", size),
+			&Element::Align(align)                 => write!(formatter, "[align={}]", align),
+		}
+	}
+}
+
+impl fmt::Display for ParseError {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		match self.kind {
+			ParseErrorKind::UnterminatedTag                  => write!(formatter, "unterminated tag"),
+			ParseErrorKind::UnknownTag(ref name)             => write!(formatter, "unknown tag \"{}\"", name),
+			ParseErrorKind::MissingValue(ref name)           => write!(formatter, "tag \"{}\" requires a value", name),
+			ParseErrorKind::InvalidValue{ref tag, ref value} => write!(formatter, "invalid value \"{}\" for tag \"{}\"", value, tag),
+			ParseErrorKind::InvalidCodepoint(ref value)      => write!(formatter, "invalid codepoint \"{}\"", value),
+		}.and_then(|_| write!(formatter, " at {}..{}", self.span.start, self.span.end))
+	}
+}
+
+impl Error for ParseError {}
+
+
+/// Escapes `[`s and `]`s by doubling them, so that the string gets printed verbatim.
+///
+/// # Examples
+///
+/// ```
+/// # use bear_lib_terminal::markup::escape;
+/// assert_eq!(escape("[color=red]"), "[[color=red]]");
+/// ```
+pub fn escape(text: &str) -> String {
+	text.replace("[", "[[").replace("]", "]]")
+}
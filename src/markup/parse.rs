@@ -0,0 +1,215 @@
+use std::char;
+use std::ops::Range;
+use geometry::{Point, Size};
+use markup::{Element, Markup, Node, ParseError, ParseErrorKind};
+
+
+/// Parse a markup string, as accepted by [`terminal::print()`](../terminal/fn.print.html).
+///
+/// `[[` and `]]` are unescaped to `[` and `]` respectively, a lone `]` is treated literally.
+///
+/// # Examples
+///
+/// ```
+/// # use bear_lib_terminal::markup::{self, Element, ParseErrorKind};
+/// # use bear_lib_terminal::geometry::{Point, Size};
+/// assert_eq!(markup::parse("[bbox=20x5][offset=-2,4][U+E001][+]^").unwrap().elements(),
+///            vec![&Element::Bbox(Size::new(20, 5)),
+///                 &Element::Offset(Point{x: -2, y: 4}),
+///                 &Element::Codepoint('\u{E001}'),
+///                 &Element::Combine,
+///                 &Element::Text("^".to_string())]);
+/// assert_eq!(markup::parse("HP: [color=red").unwrap_err().kind, ParseErrorKind::UnterminatedTag);
+/// ```
+pub fn parse(markup: &str) -> Result<Markup, ParseError> {
+	let mut nodes = vec![];
+	let mut text = String::new();
+	let mut text_start = 0;
+	let mut idx = 0;
+
+	while idx < markup.len() {
+		let rest = &markup[idx..];
+		if rest.starts_with("[[") || rest.starts_with("]]") {
+			if text.is_empty() {
+				text_start = idx;
+			}
+			text.push_str(&rest[..1]);
+			idx += 2;
+		} else if rest.starts_with('[') {
+			let end = match rest.find(']') {
+				Some(end) => idx + end + 1,
+				None      => return Err(error(ParseErrorKind::UnterminatedTag, idx..markup.len())),
+			};
+
+			if !text.is_empty() {
+				nodes.push(text_node(&mut text, text_start..idx));
+			}
+			nodes.push(Node{
+				element: parse_tag(&markup[idx + 1..end - 1], idx..end)?,
+				span: idx..end,
+			});
+			idx = end;
+		} else {
+			let c = rest.chars().next().unwrap();
+			if text.is_empty() {
+				text_start = idx;
+			}
+			text.push(c);
+			idx += c.len_utf8();
+		}
+	}
+
+	if !text.is_empty() {
+		nodes.push(text_node(&mut text, text_start..idx));
+	}
+	Ok(Markup{
+		nodes: nodes,
+	})
+}
+
+
+fn parse_tag(tag: &str, span: Range<usize>) -> Result<Element, ParseError> {
+	if tag == "+" {
+		return Ok(Element::Combine);
+	}
+	if let Some(hex) = ["U+", "u+", "0x", "0X"].iter().filter_map(|prefix| tag.strip_prefix(prefix)).next() {
+		return u32::from_str_radix(hex, 16).ok().and_then(char::from_u32).map(Element::Codepoint)
+			.ok_or_else(|| error(ParseErrorKind::InvalidCodepoint(tag.to_string()), span));
+	}
+	if let Some(name) = tag.strip_prefix('/') {
+		return match name {
+			"color"   => Ok(Element::ColorEnd),
+			"bkcolor" => Ok(Element::BkcolorEnd),
+			"font"    => Ok(Element::FontEnd),
+			"offset"  => Ok(Element::OffsetEnd),
+			name      => Err(error(ParseErrorKind::UnknownTag(name.to_string()), span)),
+		};
+	}
+
+	let mut parts = tag.splitn(2, '=');
+	let name = parts.next().unwrap().trim();
+	let value = match parts.next().map(str::trim) {
+		Some(value) if !value.is_empty() => value,
+		_                                => return Err(error(match name {
+			"color" | "bkcolor" | "font" | "offset" | "bbox" | "align" => ParseErrorKind::MissingValue(name.to_string()),
+			_                                                          => ParseErrorKind::UnknownTag(name.to_string()),
+		}, span)),
+	};
+	let invalid = || error(ParseErrorKind::InvalidValue{
+		tag: name.to_string(),
+		value: value.to_string(),
+	}, span.clone());
+
+	match name {
+		"color"   => Ok(Element::Color(value.to_string())),
+		"bkcolor" => Ok(Element::Bkcolor(value.to_string())),
+		"font"    => Ok(Element::Font(value.to_string())),
+		"offset"  => {
+			let mut coords = value.splitn(2, ',').map(|coord| coord.trim().parse::<i32>());
+			match (coords.next(), coords.next()) {
+				(Some(Ok(x)), Some(Ok(y))) => Ok(Element::Offset(Point{x: x, y: y})),
+				_                          => Err(invalid()),
+			}
+		},
+		"bbox"    => {
+			let mut dims = value.splitn(2, 'x').map(|dim| dim.trim().parse::<i32>());
+			match (dims.next(), dims.next()) {
+				(Some(Ok(width)), None) if width >= 0                            => Ok(Element::Bbox(Size::new(width, 0))),
+				(Some(Ok(width)), Some(Ok(height))) if width >= 0 && height >= 0 => Ok(Element::Bbox(Size::new(width, height))),
+				_                                                                 => Err(invalid()),
+			}
+		},
+		"align"   => value.parse().map(Element::Align).map_err(|_| invalid()),
+		_         => Err(error(ParseErrorKind::UnknownTag(name.to_string()), span)),
+	}
+}
+
+fn text_node(text: &mut String, span: Range<usize>) -> Node {
+	Node{
+		element: Element::Text(text.split_off(0)),
+		span: span,
+	}
+}
+
+fn error(kind: ParseErrorKind, span: Range<usize>) -> ParseError {
+	ParseError{
+		kind: kind,
+		span: span,
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::parse;
+	use geometry::{Alignment, HorizontalAlignment, Point, Size, VerticalAlignment};
+	use markup::{Element, Markup, ParseError, ParseErrorKind};
+
+
+	fn error(markup: &str) -> ParseError {
+		parse(markup).unwrap_err()
+	}
+
+
+	#[test]
+	fn escapes() {
+		assert_eq!(parse("[[a]] ]").unwrap().elements(), vec![&Element::Text("[a] ]".to_string())]);
+		assert_eq!(parse("").unwrap(), Markup::new(vec![]));
+	}
+
+	#[test]
+	fn spans() {
+		let parsed = parse("ab[[[color=red]é[/color]").unwrap();
+		assert_eq!(parsed.nodes.iter().map(|node| node.span.clone()).collect::<Vec<_>>(), vec![0..4, 4..15, 15..17, 17..25]);
+	}
+
+	#[test]
+	fn values() {
+		assert_eq!(parse("[bbox=7][align=center-bottom][offset=1, -1][u+41]").unwrap().elements(),
+		           vec![&Element::Bbox(Size::new(7, 0)),
+		                &Element::Align(Alignment::new(HorizontalAlignment::Center, VerticalAlignment::Bottom)),
+		                &Element::Offset(Point::new(1, -1)),
+		                &Element::Codepoint('A')]);
+	}
+
+	#[test]
+	fn round_trip() {
+		for markup in &["[color=red]HP[/color]: [[12]]", "[bkcolor=#80FF0000][font=big]x[/font][/bkcolor]", "[offset=-2,4][U+E001][+]^",
+		                "[bbox=20x5][align=middle-right]ok", "[bbox=3]"] {
+			let parsed = parse(markup).unwrap();
+			assert_eq!(parsed.to_string(), *markup);
+			assert_eq!(parse(&parsed.to_string()).unwrap(), parsed);
+		}
+	}
+
+	#[test]
+	fn unterminated_tag_spans_to_end() {
+		assert_eq!(error("HP: [color=red"), ParseError{kind: ParseErrorKind::UnterminatedTag, span: 4..14});
+		assert_eq!(error("é["), ParseError{kind: ParseErrorKind::UnterminatedTag, span: 2..3});
+	}
+
+	#[test]
+	fn tag_errors_span_the_tag() {
+		assert_eq!(error("ab[colour=red]cd"), ParseError{kind: ParseErrorKind::UnknownTag("colour".to_string()), span: 2..14});
+		assert_eq!(error("x[/bbox]"), ParseError{kind: ParseErrorKind::UnknownTag("bbox".to_string()), span: 1..8});
+		assert_eq!(error("[color=red][font=]"), ParseError{kind: ParseErrorKind::MissingValue("font".to_string()), span: 11..18});
+		assert_eq!(error("éé[U+D800]"), ParseError{kind: ParseErrorKind::InvalidCodepoint("U+D800".to_string()), span: 4..12});
+		assert_eq!(error("[0x]"), ParseError{kind: ParseErrorKind::InvalidCodepoint("0x".to_string()), span: 0..4});
+	}
+
+	#[test]
+	fn invalid_values() {
+		let invalid = |tag: &str, value: &str| ParseErrorKind::InvalidValue{tag: tag.to_string(), value: value.to_string()};
+		assert_eq!(error("[offset=1]").kind, invalid("offset", "1"));
+		assert_eq!(error("[bbox=-1]").kind, invalid("bbox", "-1"));
+		assert_eq!(error("[bbox=2x]").kind, invalid("bbox", "2x"));
+		assert_eq!(error("[align=sideways]").kind, invalid("align", "sideways"));
+		assert_eq!(error(" [bbox=-1]").span, 1..10);
+	}
+
+	#[test]
+	fn error_display() {
+		assert_eq!(error("a[b]").to_string(), "unknown tag \"b\" at 1..4");
+		assert_eq!(error("[bbox]").to_string(), "tag \"bbox\" requires a value at 0..6");
+	}
+}
@@ -0,0 +1,100 @@
+use std::fmt;
+use colors::Color;
+use geometry::Point;
+use markup::{Element, Markup};
+
+
+/// Start building a piece of styled text, see [`Styled`](struct.Styled.html).
+pub fn text<T: Into<String>>(text: T) -> Styled {
+	Styled{
+		text: text.into(),
+		foreground: None,
+		background: None,
+		font: None,
+		offset: None,
+	}
+}
+
+
+/// A piece of text with its styling, formatting to correctly escaped markup for [`terminal::print()`](../terminal/fn.print.html).
+///
+/// Every style applies only to this piece of text and is closed right after it.
+///
+/// Constructed with [`text()`](fn.text.html).
+///
+/// # Examples
+///
+/// ```
+/// use bear_lib_terminal::Color;
+/// use bear_lib_terminal::markup;
+/// use bear_lib_terminal::geometry::Point;
+///
+/// let name = markup::text("[GM] Bob").fg(Color::from_rgb(0xFF, 0x00, 0x00)).font("big");
+/// assert_eq!(name.to_string(), "[color=#FF0000][font=big][[GM]] Bob[/font][/color]");
+///
+/// let hp = markup::text("hp").bg(Color::from_rgba(0x00, 0x00, 0xFF, 0x80)).offset(Point{x: 0, y: -2});
+/// assert_eq!(format!("{}: {}", name, hp),
+///            "[color=#FF0000][font=big][[GM]] Bob[/font][/color]: [bkcolor=0x800000FF][offset=0,-2]hp[/offset][/bkcolor]");
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Styled {
+	text: String,
+	foreground: Option<Color>,
+	background: Option<Color>,
+	font: Option<String>,
+	offset: Option<Point>,
+}
+
+impl Styled {
+	/// Foreground colour of the text.
+	pub fn fg                   (mut self, color: Color)  -> Self {self.foreground = Some(color)      ; self}
+
+	/// Background colour of the text.
+	pub fn bg                   (mut self, color: Color)  -> Self {self.background = Some(color)      ; self}
+
+	/// Name of the font to use, as configured with [`config::font`](../terminal/config/font/index.html).
+	pub fn font<T: Into<String>>(mut self, font: T)       -> Self {self.font       = Some(font.into()); self}
+
+	/// Offset of the text, in pixels.
+	pub fn offset               (mut self, offset: Point) -> Self {self.offset     = Some(offset)     ; self}
+
+	/// Get the markup elements this piece of text consists of.
+	pub fn to_markup(&self) -> Markup {
+		let mut opening = vec![];
+		let mut closing = vec![];
+
+		if let Some(color) = self.foreground {
+			opening.push(Element::Color(color.to_string()));
+			closing.push(Element::ColorEnd);
+		}
+		if let Some(color) = self.background {
+			opening.push(Element::Bkcolor(color.to_string()));
+			closing.push(Element::BkcolorEnd);
+		}
+		if let Some(ref font) = self.font {
+			opening.push(Element::Font(font.clone()));
+			closing.push(Element::FontEnd);
+		}
+		if let Some(offset) = self.offset {
+			opening.push(Element::Offset(offset));
+			closing.push(Element::OffsetEnd);
+		}
+
+		opening.push(Element::Text(self.text.clone()));
+		opening.extend(closing.into_iter().rev());
+		Markup::new(opening)
+	}
+}
+
+impl From<Styled> for Markup {
+	fn from(styled: Styled) -> Markup {
+		styled.to_markup()
+	}
+}
+
+impl fmt::Display for Styled {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		write!(formatter, "{}", self.to_markup())
+	}
+}
+
@@ -0,0 +1,444 @@
+//! Pathfinding on the terminal's grid: A* and Dijkstra maps.
+//!
+//! Step costs come from a user-supplied callback, getting the point stepped from and the one stepped to,
+//! and returning `None` if the step is impossible. Points outside the bounds are never stepped onto.
+//!
+//! # Examples
+//!
+//! ```
+//! use bear_lib_terminal::path::{AStar, DijkstraMap, Movement};
+//! use bear_lib_terminal::geometry::{Point, Rect};
+//!
+//! let map = ["#######",
+//!            "#..#..#",
+//!            "#..#..#",
+//!            "#.....#",
+//!            "#######"];
+//! let cost = |_: Point, to: Point| if map[to.y as usize].as_bytes()[to.x as usize] == b'#' {None} else {Some(1.0)};
+//! let bounds = Rect::from_values(0, 0, 7, 5);
+//!
+//! let path = AStar::new(bounds, &cost).movement(Movement::EightWay).find(Point::new(1, 1), Point::new(5, 1)).unwrap();
+//! assert_eq!(path.cost, 4.0);
+//!
+//! let stairs = DijkstraMap::new(bounds, Movement::FourWay, &[Point::new(1, 1), Point::new(5, 1)], &cost);
+//! assert_eq!(stairs.path_from(Point::new(4, 3)), vec![Point::new(4, 3), Point::new(4, 2), Point::new(4, 1), Point::new(5, 1)]);
+//! ```
+
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::f32;
+use geometry::{Point, Rect};
+
+
+/// Which neighbouring cells can be stepped to.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Movement {
+	/// Orthogonally only.
+	FourWay,
+	/// Orthogonally and diagonally.
+	EightWay,
+}
+
+impl Movement {
+	/// Get the neighbours of the specified point, orthogonal ones first, clockwise from the top.
+	pub fn neighbours(&self, point: Point) -> Vec<Point> {
+		let offsets: &[(i32, i32)] = match *self {
+			Movement::FourWay  => &[(0, -1), (1, 0), (0, 1), (-1, 0)],
+			Movement::EightWay => &[(0, -1), (1, 0), (0, 1), (-1, 0), (1, -1), (1, 1), (-1, 1), (-1, -1)],
+		};
+		offsets.iter().map(|&(x, y)| Point::new(point.x + x, point.y + y)).collect()
+	}
+}
+
+
+/// An estimate of the cost between two points, for guiding [`AStar`](struct.AStar.html).
+///
+/// Heuristics are exact on an empty grid when orthogonal steps cost `1`, and diagonal ones as noted;
+/// they keep found paths optimal as long as no step is cheaper than that.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Heuristic {
+	/// `|dx| + |dy|`, for four-way movement.
+	Manhattan,
+	/// `max(|dx|, |dy|)`, diagonal steps costing `1`.
+	Chebyshev,
+	/// Diagonal steps costing `√2`.
+	Octile,
+	/// The straight-line distance, always an underestimate on a grid.
+	Euclidean,
+	/// No estimate at all, degrading A* to Dijkstra's algorithm.
+	Zero,
+}
+
+impl Heuristic {
+	/// Estimate the cost between the two points.
+	pub fn estimate(&self, from: Point, to: Point) -> f32 {
+		let (dx, dy) = ((to.x - from.x).abs() as f32, (to.y - from.y).abs() as f32);
+		match *self {
+			Heuristic::Manhattan => dx + dy,
+			Heuristic::Chebyshev => dx.max(dy),
+			Heuristic::Octile    => dx.max(dy) + (f32::consts::SQRT_2 - 1.0) * dx.min(dy),
+			Heuristic::Euclidean => (dx * dx + dy * dy).sqrt(),
+			Heuristic::Zero      => 0.0,
+		}
+	}
+}
+
+
+/// A path found by [`AStar::find()`](struct.AStar.html#method.find).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Path {
+	/// The points along the path, including both the start and the goal.
+	pub points: Vec<Point>,
+	/// The sum of the costs of all steps.
+	pub cost: f32,
+}
+
+
+/// A* pathfinder over the points within the bounds.
+///
+/// Moves four-way with the [`Manhattan`](enum.Heuristic.html#variant.Manhattan) heuristic by default,
+/// setting the movement also picks the matching heuristic, which can be overridden afterwards.
+#[derive(Clone, Debug)]
+pub struct AStar<F> {
+	bounds: Rect,
+	movement: Movement,
+	heuristic: Heuristic,
+	cost: F,
+}
+
+impl<F: Fn(Point, Point) -> Option<f32>> AStar<F> {
+	/// Construct a pathfinder over the specified bounds, with the specified step cost callback.
+	pub fn new(bounds: Rect, cost: F) -> AStar<F> {
+		AStar{
+			bounds: bounds,
+			movement: Movement::FourWay,
+			heuristic: Heuristic::Manhattan,
+			cost: cost,
+		}
+	}
+
+	/// Set the movement, along with [`Manhattan`](enum.Heuristic.html#variant.Manhattan) heuristic for four-way
+	/// or the [`Chebyshev`](enum.Heuristic.html#variant.Chebyshev) one for eight-way movement.
+	pub fn movement(mut self, movement: Movement) -> Self {
+		self.movement = movement;
+		self.heuristic = match movement {
+			Movement::FourWay  => Heuristic::Manhattan,
+			Movement::EightWay => Heuristic::Chebyshev,
+		};
+		self
+	}
+
+	/// Set the heuristic, overriding the one picked by [`movement()`](#method.movement), so call this after it.
+	///
+	/// Default: [`Manhattan`](enum.Heuristic.html#variant.Manhattan).
+	pub fn heuristic(mut self, heuristic: Heuristic) -> Self {self.heuristic = heuristic; self}
+
+	/// Find the cheapest path between the two points, or `None` if there's none or either is out of bounds.
+	pub fn find(&self, from: Point, to: Point) -> Option<Path> {
+		if !self.bounds.contains(from) || !self.bounds.contains(to) {
+			return None;
+		}
+
+		let mut costs = Grid::new(self.bounds, f32::INFINITY);
+		let mut came_from = Grid::new(self.bounds, None);
+		let mut open = BinaryHeap::new();
+		costs.set(from, 0.0);
+		open.push(Node{priority: self.heuristic.estimate(from, to), point: from});
+
+		while let Some(Node{point, ..}) = open.pop() {
+			if point == to {
+				let mut points = vec![to];
+				while let Some(previous) = came_from.get(*points.last().unwrap()) {
+					points.push(previous);
+				}
+				points.reverse();
+				return Some(Path{
+					points: points,
+					cost: costs.get(to),
+				});
+			}
+
+			let cost = costs.get(point);
+			for next in self.movement.neighbours(point).into_iter().filter(|&next| self.bounds.contains(next)) {
+				if let Some(step) = (self.cost)(point, next) {
+					if cost + step < costs.get(next) {
+						costs.set(next, cost + step);
+						came_from.set(next, Some(point));
+						open.push(Node{priority: cost + step + self.heuristic.estimate(next, to), point: next});
+					}
+				}
+			}
+		}
+		None
+	}
+}
+
+
+/// A [Dijkstra map](http://www.roguebasin.com/index.php/The_Incredible_Power_of_Dijkstra_Maps):
+/// the cost of getting to the nearest goal from every point within the bounds.
+///
+/// Walking "downhill" leads to the nearest goal; a [flee map](#method.flee) leads away from them, but not into corners.
+///
+/// # Examples
+///
+/// ```
+/// # use bear_lib_terminal::path::{DijkstraMap, Movement};
+/// # use bear_lib_terminal::geometry::{Point, Rect};
+/// let open = |_: Point, _: Point| Some(1.0);
+/// let corridor = Rect::from_values(0, 0, 10, 1);
+/// let monster = DijkstraMap::new(corridor, Movement::FourWay, &[Point::new(3, 0)], &open);
+/// assert_eq!(monster.value(Point::new(9, 0)), Some(6.0));
+///
+/// // Running from (4, 0) away from the monster leads to the far end
+/// let flee = monster.flee(1.2, &open);
+/// assert_eq!(flee.path_from(Point::new(4, 0)).last(), Some(&Point::new(9, 0)));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct DijkstraMap {
+	movement: Movement,
+	values: Grid<f32>,
+}
+
+impl DijkstraMap {
+	/// Compute the map for the specified goals, with the specified step cost callback.
+	///
+	/// The map spreads out from the goals, so the cost of a step towards them is taken to be that of the step back,
+	/// i.e. the callback gets the point closer to the goals first; that way callbacks only checking the point stepped to
+	/// keep impassable points out of the map.
+	///
+	/// Goals out of bounds are ignored.
+	pub fn new<F: Fn(Point, Point) -> Option<f32>>(bounds: Rect, movement: Movement, goals: &[Point], cost: F) -> DijkstraMap {
+		let mut values = Grid::new(bounds, f32::INFINITY);
+		for &goal in goals.iter().filter(|&&goal| bounds.contains(goal)) {
+			values.set(goal, 0.0);
+		}
+		DijkstraMap::scan(movement, values, cost)
+	}
+
+	/// Get the area the map covers.
+	pub fn area(&self) -> Rect {
+		self.values.area
+	}
+
+	/// Get the value at the specified point, or `None` if it's out of bounds or no goal can be reached from it.
+	pub fn value(&self, point: Point) -> Option<f32> {
+		if self.values.area.contains(point) {
+			Some(self.values.get(point)).filter(|value| value.is_finite())
+		} else {
+			None
+		}
+	}
+
+	/// Get the neighbour with the lowest value, if it's lower than the point's own.
+	pub fn downhill(&self, point: Point) -> Option<Point> {
+		let mut best = (point, self.value(point)?);
+		for next in self.movement.neighbours(point) {
+			match self.value(next) {
+				Some(value) if value < best.1 => best = (next, value),
+				_                              => (),
+			}
+		}
+		if best.0 == point {None} else {Some(best.0)}
+	}
+
+	/// Walk downhill from the point for as long as possible, returning all points visited, including the starting one.
+	pub fn path_from(&self, point: Point) -> Vec<Point> {
+		let mut points = vec![point];
+		while let Some(next) = self.downhill(*points.last().unwrap()) {
+			points.push(next);
+		}
+		points
+	}
+
+	/// Derive a flee map, whose downhill leads away from the goals.
+	///
+	/// Every value is multiplied by `-coefficient` and the map is rescanned, so that fleeing takes the bigger picture into account;
+	/// the usual coefficient is around `1.2`, higher ones being more willing to run past the goals to get to safety.
+	pub fn flee<F: Fn(Point, Point) -> Option<f32>>(&self, coefficient: f32, cost: F) -> DijkstraMap {
+		let mut values = self.values.clone();
+		for value in values.cells.iter_mut() {
+			*value *= -coefficient;
+		}
+		DijkstraMap::scan(self.movement, values, cost)
+	}
+
+	/// Relax the values until every point's value is at most any neighbour's plus the cost of stepping there.
+	fn scan<F: Fn(Point, Point) -> Option<f32>>(movement: Movement, mut values: Grid<f32>, cost: F) -> DijkstraMap {
+		let mut open: BinaryHeap<_> = values.area.points().filter(|&point| values.get(point).is_finite())
+		                                                     .map(|point| Node{priority: values.get(point), point: point}).collect();
+
+		let area = values.area;
+		while let Some(Node{priority, point}) = open.pop() {
+			if priority > values.get(point) {
+				continue;
+			}
+
+			for next in movement.neighbours(point).into_iter().filter(|&next| area.contains(next)) {
+				if let Some(step) = cost(point, next) {
+					if priority + step < values.get(next) {
+						values.set(next, priority + step);
+						open.push(Node{priority: priority + step, point: next});
+					}
+				}
+			}
+		}
+
+		DijkstraMap{
+			movement: movement,
+			values: values,
+		}
+	}
+}
+
+
+/// A value for every point within an area.
+#[derive(Clone, Debug, PartialEq)]
+struct Grid<T> {
+	area: Rect,
+	cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+	fn new(area: Rect, value: T) -> Grid<T> {
+		Grid{
+			area: area,
+			cells: vec![value; (area.size.width * area.size.height) as usize],
+		}
+	}
+
+	/// Callers guarantee the point's within the area.
+	fn get(&self, point: Point) -> T {
+		self.cells[self.index(point)].clone()
+	}
+
+	fn set(&mut self, point: Point, value: T) {
+		let idx = self.index(point);
+		self.cells[idx] = value;
+	}
+
+	fn index(&self, point: Point) -> usize {
+		((point.y - self.area.top_left.y) * self.area.size.width + (point.x - self.area.top_left.x)) as usize
+	}
+}
+
+/// An open point, ordered so that `BinaryHeap` pops the lowest priority first.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Node {
+	priority: f32,
+	point: Point,
+}
+
+impl Eq for Node {}
+
+impl Ord for Node {
+	fn cmp(&self, other: &Node) -> Ordering {
+		other.priority.partial_cmp(&self.priority).unwrap_or(Ordering::Equal)
+	}
+}
+
+impl PartialOrd for Node {
+	fn partial_cmp(&self, other: &Node) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::{AStar, DijkstraMap, Heuristic, Movement};
+	use geometry::{Point, Rect};
+
+
+	static MAP: [&str; 5] = ["#######",
+	                         "#..#..#",
+	                         "#..#..#",
+	                         "#.....#",
+	                         "#######"];
+
+	fn cost(_: Point, to: Point) -> Option<f32> {
+		if MAP[to.y as usize].as_bytes()[to.x as usize] == b'#' {None} else {Some(1.0)}
+	}
+
+	fn bounds() -> Rect {
+		Rect::from_values(0, 0, 7, 5)
+	}
+
+
+	#[test]
+	fn path_to_self() {
+		let path = AStar::new(bounds(), cost).find(Point::new(1, 1), Point::new(1, 1)).unwrap();
+		assert_eq!(path.points, vec![Point::new(1, 1)]);
+		assert_eq!(path.cost, 0.0);
+	}
+
+	#[test]
+	fn path_out_of_bounds_or_blocked() {
+		let astar = AStar::new(bounds(), cost);
+		assert_eq!(astar.find(Point::new(-1, 1), Point::new(1, 1)), None);
+		assert_eq!(astar.find(Point::new(1, 1), Point::new(7, 1)), None);
+		assert_eq!(astar.find(Point::new(1, 1), Point::new(3, 1)), None);
+		assert_eq!(AStar::new(Rect::from_values(0, 0, 3, 3), cost).find(Point::new(1, 1), Point::new(5, 1)), None);
+	}
+
+	#[test]
+	fn paths_are_contiguous_and_optimal() {
+		for &movement in &[Movement::FourWay, Movement::EightWay] {
+			for &heuristic in &[Heuristic::Manhattan, Heuristic::Chebyshev, Heuristic::Octile, Heuristic::Euclidean, Heuristic::Zero] {
+				let path = AStar::new(bounds(), cost).movement(movement).heuristic(heuristic).find(Point::new(1, 1), Point::new(5, 1)).unwrap();
+				assert_eq!(path.cost, if movement == Movement::FourWay {8.0} else {4.0});
+				assert_eq!(path.points.len() as f32, path.cost + 1.0);
+				for step in path.points.windows(2) {
+					assert!(movement.neighbours(step[0]).contains(&step[1]));
+					assert!(cost(step[0], step[1]).is_some());
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn path_prefers_cheap_steps() {
+		let mud = |_: Point, to: Point| if to.y == 0 {Some(5.0)} else {Some(1.0)};
+		let path = AStar::new(Rect::from_values(0, 0, 5, 2), mud).find(Point::new(0, 0), Point::new(4, 0)).unwrap();
+		assert_eq!(path.cost, 10.0);
+		assert_eq!(path.points[1..path.points.len() - 1].iter().filter(|point| point.y == 0).count(), 0);
+	}
+
+	#[test]
+	fn heuristics() {
+		let (from, to) = (Point::new(0, 0), Point::new(3, -4));
+		assert_eq!(Heuristic::Manhattan.estimate(from, to), 7.0);
+		assert_eq!(Heuristic::Chebyshev.estimate(from, to), 4.0);
+		assert!((Heuristic::Octile.estimate(from, to) - (1.0 + 3.0 * 2f32.sqrt())).abs() < 1e-5);
+		assert_eq!(Heuristic::Euclidean.estimate(from, to), 5.0);
+		assert_eq!(Heuristic::Zero.estimate(from, to), 0.0);
+	}
+
+	#[test]
+	fn dijkstra_without_goals() {
+		let map = DijkstraMap::new(bounds(), Movement::FourWay, &[Point::new(-1, -1)], cost);
+		assert!(bounds().points().all(|point| map.value(point).is_none()));
+		assert_eq!(map.path_from(Point::new(1, 1)), vec![Point::new(1, 1)]);
+	}
+
+	#[test]
+	fn dijkstra_values() {
+		let map = DijkstraMap::new(bounds(), Movement::FourWay, &[Point::new(1, 1), Point::new(5, 1)], cost);
+		assert_eq!(map.area(), bounds());
+		assert_eq!(map.value(Point::new(1, 1)), Some(0.0));
+		assert_eq!(map.value(Point::new(3, 3)), Some(4.0));
+		assert_eq!(map.value(Point::new(0, 0)), None);
+		assert_eq!(map.value(Point::new(7, 1)), None);
+		assert_eq!(map.downhill(Point::new(5, 1)), None);
+		assert_eq!(map.path_from(Point::new(3, 3)).len(), 5);
+	}
+
+	#[test]
+	fn flee_leads_away() {
+		let open = |_: Point, _: Point| Some(1.0);
+		let corridor = Rect::from_values(0, 0, 10, 1);
+		let flee = DijkstraMap::new(corridor, Movement::FourWay, &[Point::new(3, 0)], open).flee(1.2, open);
+		assert_eq!(flee.path_from(Point::new(2, 0)).last(), Some(&Point::new(0, 0)));
+		assert_eq!(flee.path_from(Point::new(4, 0)).last(), Some(&Point::new(9, 0)));
+	}
+}
@@ -0,0 +1,680 @@
+use Color;
+use markup::{self, Element};
+use std::rc::Rc;
+use std::cell::RefCell;
+use geometry::{Alignment, HorizontalAlignment, Point, Size, VerticalAlignment};
+use terminal::{Event, KeyCode, Modifiers, MODIFIERS, from_keycode, to_color_t};
+use terminal::backend::Backend;
+use std::collections::{HashMap, HashSet, VecDeque};
+use bear_lib_terminal_sys as ffi;
+
+
+/// An in-memory terminal, requiring no window.
+///
+/// Models layers, per-cell foreground and background colours, composition stacks and a scripted event queue.
+///
+/// Clones share the same screen, so a clone kept around after passing one to
+/// [`terminal::open_with()`](../fn.open_with.html) can be used to script events and inspect the output.
+///
+/// Once the scripted events run out, reading an event yields [`Event::Close`](../enum.Event.html#variant.Close),
+/// so that event loops terminate instead of blocking forever.
+#[derive(Clone, Debug)]
+pub struct Headless {
+	screen: Rc<RefCell<Screen>>,
+}
+
+#[derive(Debug)]
+struct Screen {
+	open: bool,
+	size: Size,
+	cell_size: Size,
+	layers: HashMap<i32, Vec<Vec<Leaf>>>,
+	backgrounds: Vec<Color>,
+	layer: i32,
+	foreground: Color,
+	background: Color,
+	composition: bool,
+	events: VecDeque<Event>,
+	last_event: i32,
+	wchar: i32,
+	pressed: HashSet<i32>,
+	mouse: Point,
+	mouse_pixel: Point,
+	mouse_wheel: i32,
+	clicks: i32,
+	refreshes: usize,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Glyph {
+	column: i32,
+	row: i32,
+	code: i32,
+	combined: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Leaf {
+	code: i32,
+	color: Color,
+}
+
+
+impl Headless {
+	/// Create a closed `80x25` headless terminal, which is the BearLibTerminal default size.
+	pub fn new() -> Headless {
+		Headless{
+			screen: Rc::new(RefCell::new(Screen::new(Size::new(80, 25)))),
+		}
+	}
+
+	/// Append an event to the queue, to be returned by the [`terminal::*_event()`](../index.html) functions.
+	///
+	/// The terminal state (e.g. pressed keys, mouse position or size) is updated when the event is read, not when it is queued.
+	pub fn push_event(&self, event: Event) {
+		self.screen.borrow_mut().events.push_back(event);
+	}
+
+	/// Whether the terminal is currently open.
+	pub fn is_open(&self) -> bool {
+		self.screen.borrow().open
+	}
+
+	/// Amount of times the screen has been refreshed.
+	pub fn refreshes(&self) -> usize {
+		self.screen.borrow().refreshes
+	}
+
+	/// Get the characters on row `y` of the specified layer, with the topmost character of each cell or a space for empty ones.
+	pub fn line(&self, layer: i32, y: i32) -> String {
+		let screen = self.screen.borrow();
+		(0..screen.size.width).map(|x| match screen.cell(layer, x, y).and_then(|cell| cell.last()) {
+			Some(leaf) => ::std::char::from_u32(leaf.code as u32).unwrap_or('\u{FFFD}'),
+			None       => ' ',
+		}).collect()
+	}
+}
+
+impl Default for Headless {
+	fn default() -> Headless {
+		Headless::new()
+	}
+}
+
+impl Screen {
+	fn new(size: Size) -> Screen {
+		Screen{
+			open: false,
+			size: size,
+			cell_size: Size::new(8, 16),
+			layers: HashMap::new(),
+			backgrounds: vec![Color::from_rgb(0x00, 0x00, 0x00); (size.width * size.height) as usize],
+			layer: 0,
+			foreground: Color::from_rgb(0xFF, 0xFF, 0xFF),
+			background: Color::from_rgb(0x00, 0x00, 0x00),
+			composition: false,
+			events: VecDeque::new(),
+			last_event: 0,
+			wchar: 0,
+			pressed: HashSet::new(),
+			mouse: Point::new(0, 0),
+			mouse_pixel: Point::new(0, 0),
+			mouse_wheel: 0,
+			clicks: 0,
+			refreshes: 0,
+		}
+	}
+
+	fn index(&self, x: i32, y: i32) -> Option<usize> {
+		if x >= 0 && y >= 0 && x < self.size.width && y < self.size.height {
+			Some((y * self.size.width + x) as usize)
+		} else {
+			None
+		}
+	}
+
+	fn cell(&self, layer: i32, x: i32, y: i32) -> Option<&Vec<Leaf>> {
+		match (self.index(x, y), self.layers.get(&layer)) {
+			(Some(idx), Some(cells)) => Some(&cells[idx]),
+			_                        => None,
+		}
+	}
+
+	fn resize(&mut self, size: Size) {
+		let background = self.background;
+		self.size = size;
+		self.layers.clear();
+		self.backgrounds = vec![background; (size.width * size.height) as usize];
+	}
+
+	fn put(&mut self, x: i32, y: i32, code: i32, color: Color, combined: bool) {
+		if let Some(idx) = self.index(x, y) {
+			let cells = (self.size.width * self.size.height) as usize;
+			let composition = self.composition || combined;
+			let cell = &mut self.layers.entry(self.layer).or_insert_with(|| vec![vec![]; cells])[idx];
+
+			if !composition || code == 0 {
+				cell.clear();
+			}
+			if code != 0 {
+				cell.push(Leaf{
+					code: code,
+					color: color,
+				});
+			}
+
+			if self.layer == 0 {
+				self.backgrounds[idx] = self.background;
+			}
+		}
+	}
+
+	fn clear_area(&mut self, x: i32, y: i32, width: i32, height: i32) {
+		let layer = self.layer;
+		let background = self.background;
+		for y in y..y + height {
+			for x in x..x + width {
+				if let Some(idx) = self.index(x, y) {
+					if let Some(cells) = self.layers.get_mut(&layer) {
+						cells[idx].clear();
+					}
+					if layer == 0 {
+						self.backgrounds[idx] = background;
+					}
+				}
+			}
+		}
+	}
+
+	fn print(&mut self, x: i32, y: i32, width: i32, height: i32, align: Alignment, value: &str) -> Size {
+		let color = self.foreground;
+		let (glyphs, size) = layout(value, Size::new(width, height), align);
+		for glyph in glyphs {
+			self.put(x + glyph.column, y + glyph.row, glyph.code, color, glyph.combined);
+		}
+		size
+	}
+
+	fn apply_config(&mut self, config: &str) {
+		for section in config.split(';') {
+			let mut parts = section.splitn(2, ':');
+			if let (Some(name), Some(values)) = (parts.next(), parts.next()) {
+				if name.trim() == "window" {
+					for value in values.split(',') {
+						let mut kv = value.splitn(2, '=');
+						match (kv.next().map(str::trim), kv.next().and_then(parse_size)) {
+							(Some("size"), Some(size))     => self.resize(size),
+							(Some("cellsize"), Some(size)) => self.cell_size = size,
+							_                              => (),
+						}
+					}
+				}
+			}
+		}
+	}
+
+	fn event_code(event: &Event) -> i32 {
+		match *event {
+			Event::Close                   => ffi::TK_CLOSE,
+			Event::Resize{..}              => ffi::TK_RESIZED,
+			Event::MouseMove{..}           => ffi::TK_MOUSE_MOVE,
+			Event::MouseMovePrecise{..}    => ffi::TK_MOUSE_MOVE,
+			Event::MouseDown{button, ..}   => from_keycode(button.into()),
+			Event::MouseUp{button, ..}     => from_keycode(button.into()) | ffi::TK_KEY_RELEASED,
+			Event::MouseScroll{..}         => ffi::TK_MOUSE_SCROLL,
+			Event::KeyPressed{key, ..}     => from_keycode(key),
+			Event::KeyReleased{key, ..}    => from_keycode(key) | ffi::TK_KEY_RELEASED,
+			Event::ShiftPressed            => ffi::TK_SHIFT,
+			Event::ShiftReleased           => ffi::TK_SHIFT | ffi::TK_KEY_RELEASED,
+			Event::ControlPressed          => ffi::TK_CONTROL,
+			Event::ControlReleased         => ffi::TK_CONTROL | ffi::TK_KEY_RELEASED,
+			Event::AltPressed              => ffi::TK_ALT,
+			Event::AltReleased             => ffi::TK_ALT | ffi::TK_KEY_RELEASED,
+		}
+	}
+
+	fn apply_event(&mut self, event: &Event) {
+		self.wchar = 0;
+		match *event {
+			Event::Resize{width, height}     => self.resize(Size::new(width, height)),
+			Event::MouseMove{x, y, modifiers} => {
+				self.hold(modifiers);
+				self.mouse = Point::new(x, y);
+				self.mouse_pixel = Point::new(x * self.cell_size.width, y * self.cell_size.height);
+			},
+			Event::MouseMovePrecise{cell, pixel, modifiers} => {
+				self.hold(modifiers);
+				self.mouse = cell;
+				self.mouse_pixel = pixel;
+			},
+			Event::MouseDown{button, cell, pixel, clicks, modifiers} => {
+				self.hold(modifiers);
+				self.press(from_keycode(button.into()), true);
+				self.mouse = cell;
+				self.mouse_pixel = pixel;
+				self.clicks = clicks;
+			},
+			Event::MouseUp{button, cell, pixel, clicks, modifiers} => {
+				self.hold(modifiers);
+				self.press(from_keycode(button.into()), false);
+				self.mouse = cell;
+				self.mouse_pixel = pixel;
+				self.clicks = clicks;
+			},
+			Event::MouseScroll{delta, modifiers} => {
+				self.hold(modifiers);
+				self.mouse_wheel = delta;
+			},
+			Event::KeyPressed{key, modifiers, text} => {
+				self.wchar = text.map(|c| c as i32).unwrap_or(0);
+				self.press_key(key, modifiers, true);
+			},
+			Event::KeyReleased{key, modifiers} => self.press_key(key, modifiers, false),
+			Event::ShiftPressed              => self.press(ffi::TK_SHIFT, true),
+			Event::ShiftReleased             => self.press(ffi::TK_SHIFT, false),
+			Event::ControlPressed            => self.press(ffi::TK_CONTROL, true),
+			Event::ControlReleased           => self.press(ffi::TK_CONTROL, false),
+			Event::AltPressed                => self.press(ffi::TK_ALT, true),
+			Event::AltReleased               => self.press(ffi::TK_ALT, false),
+			Event::Close                     => (),
+		}
+	}
+
+	fn press_key(&mut self, key: KeyCode, modifiers: Modifiers, pressed: bool) {
+		self.hold(modifiers);
+		self.press(from_keycode(key), pressed);
+	}
+
+	/// Press exactly the specified modifiers, releasing all others.
+	fn hold(&mut self, modifiers: Modifiers) {
+		for &(code, modifier) in MODIFIERS.iter() {
+			self.press(code, modifiers.contains(modifier));
+		}
+	}
+
+	fn press(&mut self, code: i32, pressed: bool) {
+		if pressed {
+			self.pressed.insert(code);
+		} else {
+			self.pressed.remove(&code);
+		}
+	}
+}
+
+impl Backend for Headless {
+	fn open(&mut self) -> bool {
+		let mut screen = self.screen.borrow_mut();
+		if screen.open {
+			false
+		} else {
+			let size = screen.size;
+			screen.resize(size);
+			screen.open = true;
+			true
+		}
+	}
+
+	fn close(&mut self) {
+		self.screen.borrow_mut().open = false;
+	}
+
+	fn set(&mut self, config: &str) -> bool {
+		self.screen.borrow_mut().apply_config(config);
+		true
+	}
+
+	fn refresh(&mut self) {
+		self.screen.borrow_mut().refreshes += 1;
+	}
+
+	fn clear(&mut self) {
+		let mut screen = self.screen.borrow_mut();
+		let size = screen.size;
+		screen.resize(size);
+	}
+
+	fn clear_area(&mut self, x: i32, y: i32, width: i32, height: i32) {
+		self.screen.borrow_mut().clear_area(x, y, width, height);
+	}
+
+	/// Cropping only affects what is shown on screen, not the cells' contents, so there's nothing to do here.
+	fn crop(&mut self, _: i32, _: i32, _: i32, _: i32) {}
+
+	fn layer(&mut self, index: i32) {
+		self.screen.borrow_mut().layer = index;
+	}
+
+	fn color(&mut self, color: Color) {
+		self.screen.borrow_mut().foreground = color;
+	}
+
+	fn bkcolor(&mut self, color: Color) {
+		self.screen.borrow_mut().background = color;
+	}
+
+	fn composition(&mut self, enable: bool) {
+		self.screen.borrow_mut().composition = enable;
+	}
+
+	fn put(&mut self, x: i32, y: i32, code: i32) {
+		let mut screen = self.screen.borrow_mut();
+		let color = screen.foreground;
+		screen.put(x, y, code, color, false);
+	}
+
+	fn put_ext(&mut self, x: i32, y: i32, _: i32, _: i32, code: i32, corners: Option<[Color; 4]>) {
+		let mut screen = self.screen.borrow_mut();
+		let color = corners.map(|corners| corners[0]).unwrap_or(screen.foreground);
+		screen.put(x, y, code, color, false);
+	}
+
+	fn pick(&mut self, x: i32, y: i32, index: i32) -> i32 {
+		let screen = self.screen.borrow();
+		screen.cell(screen.layer, x, y).and_then(|cell| cell.get(index as usize)).map(|leaf| leaf.code).unwrap_or(0)
+	}
+
+	fn pick_color(&mut self, x: i32, y: i32, index: i32) -> Color {
+		let screen = self.screen.borrow();
+		screen.cell(screen.layer, x, y).and_then(|cell| cell.get(index as usize)).map(|leaf| leaf.color).unwrap_or(Color::from_rgba(0, 0, 0, 0))
+	}
+
+	fn pick_bkcolor(&mut self, x: i32, y: i32) -> Color {
+		let screen = self.screen.borrow();
+		screen.index(x, y).map(|idx| screen.backgrounds[idx]).unwrap_or(Color::from_rgba(0, 0, 0, 0))
+	}
+
+	fn print_ext(&mut self, x: i32, y: i32, width: i32, height: i32, align: Alignment, value: &str) -> Size {
+		self.screen.borrow_mut().print(x, y, width, height, align, value)
+	}
+
+	/// Returns the height if there's a `bbox` tag in the string, otherwise the width.
+	fn measure(&mut self, value: &str) -> i32 {
+		let size = self.measure_ext(0, 0, value);
+		match markup::parse(value) {
+			Ok(ref parsed) if parsed.nodes.iter().any(|node| matches!(node.element, Element::Bbox(_))) => size.height,
+			_                                                                                         => size.width,
+		}
+	}
+
+	fn measure_ext(&mut self, width: i32, height: i32, value: &str) -> Size {
+		layout(value, Size::new(width, height), Alignment::default()).1
+	}
+
+	fn state(&mut self, slot: i32) -> i32 {
+		let screen = self.screen.borrow();
+		match slot {
+			ffi::TK_WIDTH        => screen.size.width,
+			ffi::TK_HEIGHT       => screen.size.height,
+			ffi::TK_CELL_WIDTH   => screen.cell_size.width,
+			ffi::TK_CELL_HEIGHT  => screen.cell_size.height,
+			ffi::TK_COLOR        => to_color_t(screen.foreground) as i32,
+			ffi::TK_BKCOLOR      => to_color_t(screen.background) as i32,
+			ffi::TK_LAYER        => screen.layer,
+			ffi::TK_COMPOSITION  => screen.composition as i32,
+			ffi::TK_EVENT        => screen.last_event,
+			ffi::TK_WCHAR        => screen.wchar,
+			ffi::TK_MOUSE_X      => screen.mouse.x,
+			ffi::TK_MOUSE_Y      => screen.mouse.y,
+			ffi::TK_MOUSE_PIXEL_X => screen.mouse_pixel.x,
+			ffi::TK_MOUSE_PIXEL_Y => screen.mouse_pixel.y,
+			ffi::TK_MOUSE_WHEEL  => screen.mouse_wheel,
+			ffi::TK_MOUSE_CLICKS => screen.clicks,
+			code                 => screen.pressed.contains(&code) as i32,
+		}
+	}
+
+	fn check(&mut self, slot: i32) -> bool {
+		self.state(slot) != 0
+	}
+
+	fn has_input(&mut self) -> bool {
+		!self.screen.borrow().events.is_empty()
+	}
+
+	fn read(&mut self) -> i32 {
+		let mut screen = self.screen.borrow_mut();
+		let code = match screen.events.pop_front() {
+			Some(event) => {
+				screen.apply_event(&event);
+				Screen::event_code(&event)
+			},
+			None => ffi::TK_CLOSE,
+		};
+		screen.last_event = code;
+		code
+	}
+
+	fn peek(&mut self) -> i32 {
+		self.screen.borrow().events.front().map(Screen::event_code).unwrap_or(0)
+	}
+
+	fn read_str(&mut self, _: i32, _: i32, _: i32) -> Option<String> {
+		None
+	}
+
+	fn delay(&mut self, _: i32) {}
+}
+
+
+/// Lay out the characters of a markup string inside a box, relative to its origin, returning them along with the text's dimensions.
+///
+/// The text is word-wrapped if the box has a width; `bbox` and `align` tags override the box and alignment.
+///
+/// Colours, fonts and offsets are disregarded; unparseable markup is laid out literally.
+fn layout(value: &str, mut bbox: Size, mut align: Alignment) -> (Vec<Glyph>, Size) {
+	let elements = match markup::parse(value) {
+		Ok(parsed) => parsed.nodes.into_iter().map(|node| node.element).collect(),
+		Err(_)     => vec![Element::Text(value.to_string())],
+	};
+
+	// Each line is a list of clusters: a character with all the ones combined onto it
+	let mut lines: Vec<Vec<Vec<char>>> = vec![vec![]];
+	let mut combine = false;
+	for element in elements {
+		let chars = match element {
+			Element::Text(text)          => text.chars().collect(),
+			Element::Codepoint(codepoint) => vec![codepoint],
+			Element::Combine             => {
+				combine = true;
+				vec![]
+			},
+			Element::Bbox(size)          => {
+				bbox = size;
+				vec![]
+			},
+			Element::Align(alignment)    => {
+				align = alignment;
+				vec![]
+			},
+			_                            => vec![],
+		};
+
+		for c in chars {
+			let line = lines.last_mut().unwrap();
+			if c == '\n' {
+				lines.push(vec![]);
+			} else if combine && !line.is_empty() {
+				line.last_mut().unwrap().push(c);
+			} else {
+				line.push(vec![c]);
+			}
+			combine = false;
+		}
+	}
+	if lines.len() == 1 && lines[0].is_empty() {
+		return (vec![], Size::new(0, 0));
+	}
+
+	let lines: Vec<_> = lines.into_iter().flat_map(|line| wrap(line, bbox.width as usize)).collect();
+	let size = Size::new(lines.iter().map(|line| line.len() as i32).max().unwrap_or(0), lines.len() as i32);
+
+	// An unbounded dimension aligns within the text's own extent
+	let frame = Size::new(if bbox.width  == 0 {size.width}  else {bbox.width},
+	                      if bbox.height == 0 {size.height} else {bbox.height});
+	let top = match align.vertical {
+		VerticalAlignment::Default | VerticalAlignment::Top => 0,
+		VerticalAlignment::Middle                           => (frame.height - size.height) / 2,
+		VerticalAlignment::Bottom                           => frame.height - size.height,
+	};
+	let mut glyphs = vec![];
+	for (row, line) in lines.into_iter().enumerate() {
+		let left = match align.horizontal {
+			HorizontalAlignment::Default | HorizontalAlignment::Left => 0,
+			HorizontalAlignment::Center                              => (frame.width - line.len() as i32) / 2,
+			HorizontalAlignment::Right                               => frame.width - line.len() as i32,
+		};
+
+		for (column, cluster) in line.into_iter().enumerate() {
+			for (i, c) in cluster.into_iter().enumerate() {
+				glyphs.push(Glyph{
+					column: left + column as i32,
+					row: top + row as i32,
+					code: c as i32,
+					combined: i != 0,
+				});
+			}
+		}
+	}
+	(glyphs, size)
+}
+
+/// Greedily word-wrap a line to the specified width, which, if `0`, means no wrapping.
+///
+/// Spaces are kept as they are, except for those at a break, which are dropped; words longer than the width are broken up.
+fn wrap(line: Vec<Vec<char>>, width: usize) -> Vec<Vec<Vec<char>>> {
+	if width == 0 || line.len() <= width {
+		return vec![line];
+	}
+
+	let mut lines = vec![];
+	let mut current: Vec<Vec<char>> = vec![];
+	let mut rest = &line[..];
+	while !rest.is_empty() {
+		let spaces = rest.iter().take_while(|cluster| cluster[0] == ' ').count();
+		let letters = rest[spaces..].iter().take_while(|cluster| cluster[0] != ' ').count();
+		let (gap, word) = rest[..spaces + letters].split_at(spaces);
+		rest = &rest[spaces + letters..];
+
+		if current.len() + gap.len() + word.len() <= width {
+			current.extend_from_slice(gap);
+			current.extend_from_slice(word);
+		} else {
+			if !current.is_empty() {
+				lines.push(current);
+			}
+
+			let mut chunks: Vec<_> = word.chunks(width).map(|chunk| chunk.to_vec()).collect();
+			current = chunks.pop().unwrap_or_default();
+			lines.extend(chunks);
+		}
+	}
+	if !current.is_empty() || lines.is_empty() {
+		lines.push(current);
+	}
+	lines
+}
+
+fn parse_size(value: &str) -> Option<Size> {
+	let mut dims = value.trim().splitn(2, 'x').map(|dim| dim.parse::<i32>());
+	match (dims.next(), dims.next()) {
+		(Some(Ok(width)), Some(Ok(height))) if width >= 0 && height >= 0 => Some(Size::new(width, height)),
+		_                                                                 => None,
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::Headless;
+	use terminal::backend::Backend;
+	use geometry::{Alignment, HorizontalAlignment, Size, VerticalAlignment};
+
+
+	fn screen() -> Headless {
+		let mut backend = Headless::new();
+		backend.set("window: size=10x4");
+		backend.open();
+		backend
+	}
+
+	fn lines(backend: &Headless) -> Vec<String> {
+		(0..4).map(|y| backend.line(0, y)).collect()
+	}
+
+	fn aligned(horizontal: HorizontalAlignment, vertical: VerticalAlignment) -> Alignment {
+		Alignment::new(horizontal, vertical)
+	}
+
+
+	#[test]
+	fn wrap_breaks_at_spaces() {
+		let mut backend = screen();
+		assert_eq!(backend.print_ext(0, 0, 10, 0, Alignment::default(), "the quick brown fox"), Size::new(9, 2));
+		assert_eq!(lines(&backend), vec!["the quick ", "brown fox ", "          ", "          "]);
+	}
+
+	#[test]
+	fn wrap_drops_spaces_at_break_only() {
+		let mut backend = screen();
+		assert_eq!(backend.print_ext(0, 0, 4, 0, Alignment::default(), "a  b cccc"), Size::new(4, 2));
+		assert_eq!(lines(&backend)[..2], ["a  b      ", "cccc      "]);
+	}
+
+	#[test]
+	fn wrap_splits_long_words() {
+		let mut backend = screen();
+		assert_eq!(backend.print_ext(0, 0, 4, 0, Alignment::default(), "abcdefghij"), Size::new(4, 3));
+		assert_eq!(lines(&backend)[..3], ["abcd      ", "efgh      ", "ij        "]);
+	}
+
+	#[test]
+	fn wrap_keeps_newlines() {
+		let mut backend = screen();
+		assert_eq!(backend.print_ext(0, 0, 0, 0, Alignment::default(), "ab\n\ncd"), Size::new(2, 3));
+		assert_eq!(lines(&backend)[..3], ["ab        ", "          ", "cd        "]);
+	}
+
+	#[test]
+	fn align_within_box() {
+		let mut backend = screen();
+		backend.print_ext(0, 0, 10, 4, aligned(HorizontalAlignment::Right, VerticalAlignment::Bottom), "ab");
+		assert_eq!(lines(&backend)[3], "        ab");
+
+		let mut backend = screen();
+		backend.print_ext(0, 0, 10, 4, aligned(HorizontalAlignment::Center, VerticalAlignment::Middle), "ab");
+		assert_eq!(lines(&backend)[1], "    ab    ");
+	}
+
+	#[test]
+	fn align_unbounded_within_text() {
+		let mut backend = screen();
+		assert_eq!(backend.print_ext(2, 0, 0, 0, aligned(HorizontalAlignment::Center, VerticalAlignment::Default), "abcd\nab"), Size::new(4, 2));
+		assert_eq!(lines(&backend)[..2], ["  abcd    ", "   ab     "]);
+	}
+
+	#[test]
+	fn tags_override_box_and_alignment() {
+		let mut backend = screen();
+		assert_eq!(backend.print_ext(0, 0, 0, 0, Alignment::default(), "[bbox=5][align=right]abc def"), Size::new(3, 2));
+		assert_eq!(lines(&backend)[..2], ["  abc     ", "  def     "]);
+		assert_eq!(backend.measure("[bbox=5]abc def"), 2);
+		assert_eq!(backend.measure("abc def"), 7);
+	}
+
+	#[test]
+	fn combined_characters_share_a_cell() {
+		let mut backend = screen();
+		assert_eq!(backend.print_ext(0, 0, 0, 0, Alignment::default(), "a[+]^b"), Size::new(2, 1));
+		assert_eq!(backend.pick(0, 0, 0), 'a' as i32);
+		assert_eq!(backend.pick(0, 0, 1), '^' as i32);
+		assert_eq!(backend.pick(1, 0, 0), 'b' as i32);
+	}
+
+	#[test]
+	fn invalid_markup_is_literal() {
+		let mut backend = screen();
+		assert_eq!(backend.print_ext(0, 0, 0, 0, Alignment::default(), "[x]"), Size::new(3, 1));
+		assert_eq!(lines(&backend)[0], "[x]       ");
+		assert_eq!(backend.measure_ext(0, 0, ""), Size::new(0, 0));
+	}
+}
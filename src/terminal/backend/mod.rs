@@ -0,0 +1,105 @@
+//! Pluggable implementations of the primitives the [`terminal`](../index.html) module is built upon.
+//!
+//! By default every function in the [`terminal`](../index.html) module talks to [BearLibTerminal](https://bitbucket.org/cfyzium/bearlibterminal)
+//! itself via the [`Native`](struct.Native.html) backend.
+//!
+//! The [`Headless`](struct.Headless.html) backend is a pure-Rust in-memory model of the terminal, which needs neither a window
+//! nor a graphical environment, so rendering code can be asserted cell-by-cell in tests.
+//!
+//! The backend is selected when opening the terminal with [`terminal::open_with()`](../fn.open_with.html)
+//! and reverts to [`Native`](struct.Native.html) on [`terminal::close()`](../fn.close.html).
+//!
+//! # Examples
+//!
+//! ```
+//! use bear_lib_terminal::Color;
+//! use bear_lib_terminal::geometry::Point;
+//! use bear_lib_terminal::terminal::{self, Event, KeyCode, Modifiers};
+//! use bear_lib_terminal::terminal::backend::Headless;
+//!
+//! let backend = Headless::new();
+//! terminal::open_with(backend.clone(), "Headless example", 20, 5).unwrap();
+//!
+//! terminal::set_foreground(Color::from_rgb(0xFF, 0x00, 0x00));
+//! terminal::print_xy(1, 2, "[[HP]]");
+//! assert_eq!(backend.line(0, 2), " [HP]               ");
+//! assert_eq!(terminal::pick(Point::new(2, 2), 0), Ok('H'));
+//! assert_eq!(terminal::pick_foreground_color(Point::new(2, 2), 0), Ok(Color::from_rgb(0xFF, 0x00, 0x00)));
+//!
+//! backend.push_event(Event::KeyPressed{key: KeyCode::Escape, modifiers: Modifiers::empty(), text: None});
+//! assert_eq!(terminal::wait_event(), Some(Event::KeyPressed{key: KeyCode::Escape, modifiers: Modifiers::empty(), text: None}));
+//! assert_eq!(terminal::wait_event(), Some(Event::Close));
+//!
+//! terminal::close();
+//! ```
+
+
+mod native;
+mod headless;
+
+use Color;
+use geometry::{Alignment, Size};
+
+pub use self::native::Native;
+pub use self::headless::Headless;
+
+
+/// The primitive operations behind the [`terminal`](../index.html) module.
+///
+/// These mirror the [BearLibTerminal C API](http://foo.wyrd.name/en:bearlibterminal:reference) one-to-one;
+/// event and state codes are the `TK_*` constants from [`bear_lib_terminal_sys`](https://crates.io/crates/bear-lib-terminal-sys).
+pub trait Backend {
+	/// [`terminal_open()`](http://foo.wyrd.name/en:bearlibterminal:reference#open).
+	fn open(&mut self) -> bool;
+	/// [`terminal_close()`](http://foo.wyrd.name/en:bearlibterminal:reference#close).
+	fn close(&mut self);
+	/// [`terminal_set()`](http://foo.wyrd.name/en:bearlibterminal:reference#set).
+	fn set(&mut self, config: &str) -> bool;
+	/// [`terminal_refresh()`](http://foo.wyrd.name/en:bearlibterminal:reference#refresh).
+	fn refresh(&mut self);
+	/// [`terminal_clear()`](http://foo.wyrd.name/en:bearlibterminal:reference#clear).
+	fn clear(&mut self);
+	/// [`terminal_clear_area()`](http://foo.wyrd.name/en:bearlibterminal:reference#clear_area).
+	fn clear_area(&mut self, x: i32, y: i32, width: i32, height: i32);
+	/// [`terminal_crop()`](http://foo.wyrd.name/en:bearlibterminal:reference#crop).
+	fn crop(&mut self, x: i32, y: i32, width: i32, height: i32);
+	/// [`terminal_layer()`](http://foo.wyrd.name/en:bearlibterminal:reference#layer).
+	fn layer(&mut self, index: i32);
+	/// [`terminal_color()`](http://foo.wyrd.name/en:bearlibterminal:reference#color).
+	fn color(&mut self, color: Color);
+	/// [`terminal_bkcolor()`](http://foo.wyrd.name/en:bearlibterminal:reference#bkcolor).
+	fn bkcolor(&mut self, color: Color);
+	/// [`terminal_composition()`](http://foo.wyrd.name/en:bearlibterminal:reference#composition).
+	fn composition(&mut self, enable: bool);
+	/// [`terminal_put()`](http://foo.wyrd.name/en:bearlibterminal:reference#put).
+	fn put(&mut self, x: i32, y: i32, code: i32);
+	/// [`terminal_put_ext()`](http://foo.wyrd.name/en:bearlibterminal:reference#put_ext),
+	/// the corners being top-left, bottom-left, bottom-right, top-right, or the current colour if `None`.
+	fn put_ext(&mut self, x: i32, y: i32, dx: i32, dy: i32, code: i32, corners: Option<[Color; 4]>);
+	/// [`terminal_pick()`](http://foo.wyrd.name/en:bearlibterminal:reference#pick).
+	fn pick(&mut self, x: i32, y: i32, index: i32) -> i32;
+	/// [`terminal_pick_color()`](http://foo.wyrd.name/en:bearlibterminal:reference#pick_color).
+	fn pick_color(&mut self, x: i32, y: i32, index: i32) -> Color;
+	/// [`terminal_pick_bkcolor()`](http://foo.wyrd.name/en:bearlibterminal:reference#pick_bkcolor).
+	fn pick_bkcolor(&mut self, x: i32, y: i32) -> Color;
+	/// [`terminal_print_ext()`](http://foo.wyrd.name/en:bearlibterminal:reference#print).
+	fn print_ext(&mut self, x: i32, y: i32, width: i32, height: i32, align: Alignment, value: &str) -> Size;
+	/// [`terminal_measure()`](http://foo.wyrd.name/en:bearlibterminal:reference#measure).
+	fn measure(&mut self, value: &str) -> i32;
+	/// [`terminal_measure_ext()`](http://foo.wyrd.name/en:bearlibterminal:reference#measure).
+	fn measure_ext(&mut self, width: i32, height: i32, value: &str) -> Size;
+	/// [`terminal_state()`](http://foo.wyrd.name/en:bearlibterminal:reference#state).
+	fn state(&mut self, slot: i32) -> i32;
+	/// [`terminal_check()`](http://foo.wyrd.name/en:bearlibterminal:reference#check).
+	fn check(&mut self, slot: i32) -> bool;
+	/// [`terminal_has_input()`](http://foo.wyrd.name/en:bearlibterminal:reference#has_input).
+	fn has_input(&mut self) -> bool;
+	/// [`terminal_read()`](http://foo.wyrd.name/en:bearlibterminal:reference#read).
+	fn read(&mut self) -> i32;
+	/// [`terminal_peek()`](http://foo.wyrd.name/en:bearlibterminal:reference#peek).
+	fn peek(&mut self) -> i32;
+	/// [`terminal_read_str()`](http://foo.wyrd.name/en:bearlibterminal:reference#read_str).
+	fn read_str(&mut self, x: i32, y: i32, max: i32) -> Option<String>;
+	/// [`terminal_delay()`](http://foo.wyrd.name/en:bearlibterminal:reference#delay).
+	fn delay(&mut self, period: i32);
+}
@@ -0,0 +1,153 @@
+use std::ffi::CString;
+use std::os::raw::c_char;
+use Color;
+use geometry::{Alignment, HorizontalAlignment, Size, VerticalAlignment};
+use terminal::{from_color_t, to_color_t};
+use terminal::backend::Backend;
+use bear_lib_terminal_sys as ffi;
+
+
+/// The default backend, forwarding everything to [BearLibTerminal](https://bitbucket.org/cfyzium/bearlibterminal) itself.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Native;
+
+impl Backend for Native {
+	fn open(&mut self) -> bool {
+		ffi::open()
+	}
+
+	fn close(&mut self) {
+		ffi::close();
+	}
+
+	fn set(&mut self, config: &str) -> bool {
+		ffi::set(config)
+	}
+
+	fn refresh(&mut self) {
+		ffi::refresh();
+	}
+
+	fn clear(&mut self) {
+		ffi::clear();
+	}
+
+	fn clear_area(&mut self, x: i32, y: i32, width: i32, height: i32) {
+		ffi::clear_area(x, y, width, height);
+	}
+
+	fn crop(&mut self, x: i32, y: i32, width: i32, height: i32) {
+		ffi::crop(x, y, width, height);
+	}
+
+	fn layer(&mut self, index: i32) {
+		ffi::layer(index);
+	}
+
+	fn color(&mut self, color: Color) {
+		ffi::color(to_color_t(color));
+	}
+
+	fn bkcolor(&mut self, color: Color) {
+		ffi::bkcolor(to_color_t(color));
+	}
+
+	fn composition(&mut self, enable: bool) {
+		ffi::composition(enable);
+	}
+
+	fn put(&mut self, x: i32, y: i32, code: i32) {
+		ffi::put(x, y, code);
+	}
+
+	fn put_ext(&mut self, x: i32, y: i32, dx: i32, dy: i32, code: i32, corners: Option<[Color; 4]>) {
+		let corners = match corners {
+			Some(corners) => corners.iter().cloned().map(to_color_t).collect(),
+			None          => vec![ffi::state(ffi::TK_COLOR) as ffi::ColorT; 4],
+		};
+		ffi::put_ext(x, y, dx, dy, code, &corners[..]);
+	}
+
+	fn pick(&mut self, x: i32, y: i32, index: i32) -> i32 {
+		ffi::pick(x, y, index)
+	}
+
+	fn pick_color(&mut self, x: i32, y: i32, index: i32) -> Color {
+		from_color_t(ffi::pick_color(x, y, index))
+	}
+
+	fn pick_bkcolor(&mut self, x: i32, y: i32) -> Color {
+		from_color_t(ffi::pick_bkcolor(x, y))
+	}
+
+	fn print_ext(&mut self, x: i32, y: i32, width: i32, height: i32, align: Alignment, value: &str) -> Size {
+		let value = CString::new(value).unwrap();
+		let (mut out_w, mut out_h) = (0, 0);
+		unsafe {
+			terminal_print_ext8(x, y, width, height, to_align_flags(align), value.as_ptr(), &mut out_w, &mut out_h);
+		}
+		Size::new(out_w, out_h)
+	}
+
+	fn measure(&mut self, value: &str) -> i32 {
+		ffi::measure(value)
+	}
+
+	fn measure_ext(&mut self, width: i32, height: i32, value: &str) -> Size {
+		let value = CString::new(value).unwrap();
+		let (mut out_w, mut out_h) = (0, 0);
+		unsafe {
+			terminal_measure_ext8(width, height, value.as_ptr(), &mut out_w, &mut out_h);
+		}
+		Size::new(out_w, out_h)
+	}
+
+	fn state(&mut self, slot: i32) -> i32 {
+		ffi::state(slot)
+	}
+
+	fn check(&mut self, slot: i32) -> bool {
+		ffi::check(slot)
+	}
+
+	fn has_input(&mut self) -> bool {
+		ffi::has_input()
+	}
+
+	fn read(&mut self) -> i32 {
+		ffi::read()
+	}
+
+	fn peek(&mut self) -> i32 {
+		ffi::peek()
+	}
+
+	fn read_str(&mut self, x: i32, y: i32, max: i32) -> Option<String> {
+		ffi::read_str(x, y, max)
+	}
+
+	fn delay(&mut self, period: i32) {
+		ffi::delay(period);
+	}
+}
+
+
+// The sys crate keeps these private, so link against them directly; the library itself is already linked in by it.
+extern "C" {
+	fn terminal_print_ext8(x: i32, y: i32, width: i32, height: i32, align: i32, value: *const c_char, out_w: *mut i32, out_h: *mut i32);
+	fn terminal_measure_ext8(width: i32, height: i32, value: *const c_char, out_w: *mut i32, out_h: *mut i32);
+}
+
+fn to_align_flags(align: Alignment) -> i32 {
+	(match align.horizontal {
+		HorizontalAlignment::Default => ffi::TK_ALIGN_DEFAULT,
+		HorizontalAlignment::Left    => ffi::TK_ALIGN_LEFT,
+		HorizontalAlignment::Center  => ffi::TK_ALIGN_CENTER,
+		HorizontalAlignment::Right   => ffi::TK_ALIGN_RIGHT,
+	}) | (match align.vertical {
+		VerticalAlignment::Default => ffi::TK_ALIGN_DEFAULT,
+		VerticalAlignment::Top     => ffi::TK_ALIGN_TOP,
+		VerticalAlignment::Middle  => ffi::TK_ALIGN_MIDDLE,
+		VerticalAlignment::Bottom  => ffi::TK_ALIGN_BOTTOM,
+	})
+}
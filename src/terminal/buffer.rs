@@ -0,0 +1,485 @@
+//! Off-screen cell grids, for composing output before drawing it onto the terminal.
+//!
+//! Use [`blit()`](fn.blit.html) to draw a buffer once, or a [`Renderer`](struct.Renderer.html) to redraw only what changed between frames.
+//!
+//! # Examples
+//!
+//! ```
+//! use bear_lib_terminal::Color;
+//! use bear_lib_terminal::terminal::{self, buffer};
+//! use bear_lib_terminal::terminal::buffer::{Cell, CellBuffer};
+//! use bear_lib_terminal::terminal::backend::Headless;
+//! use bear_lib_terminal::geometry::{Point, Rect, Size};
+//!
+//! let white = Color::from_rgb(0xFF, 0xFF, 0xFF);
+//! let blue = Color::from_rgb(0x00, 0x00, 0xFF);
+//!
+//! let mut panel = CellBuffer::new(Size::new(6, 3));
+//! panel.fill(Rect::from_values(0, 0, 6, 3), Cell::new('.', white, blue));
+//! panel.view(Rect::from_values(1, 1, 4, 1)).print(Point::new(0, 0), "Status", white, blue);
+//! assert_eq!(panel.get(Point::new(4, 1)).map(|cell| cell.ch), Some('t'));
+//! assert_eq!(panel.get(Point::new(5, 1)).map(|cell| cell.ch), Some('.'));
+//!
+//! let backend = Headless::new();
+//! terminal::open_with(backend.clone(), "buffer example", 10, 5).unwrap();
+//! buffer::blit(&panel, Point::new(2, 1), 0);
+//! assert_eq!(backend.line(0, 2), "  .Stat.  ");
+//! assert_eq!(terminal::pick_background_color(Point::new(2, 1)), Ok(blue));
+//! terminal::close();
+//! ```
+
+
+use std::cmp;
+use std::collections::BTreeMap;
+use colors::Color;
+use geometry::{Point, Rect, Size};
+use terminal::{self, state};
+
+
+/// A single character with its colours.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Cell {
+	pub ch: char,
+	pub foreground: Color,
+	pub background: Color,
+}
+
+/// A 2D grid of optionally empty [`Cell`](struct.Cell.html)s.
+///
+/// Empty cells are not drawn by [`blit()`](fn.blit.html), letting whatever was underneath show through.
+///
+/// All operations are clipped to the buffer's bounds.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct CellBuffer {
+	size: Size,
+	cells: Vec<Option<Cell>>,
+}
+
+/// A mutable rectangular window into a [`CellBuffer`](struct.CellBuffer.html), constructed with
+/// [`CellBuffer::view()`](struct.CellBuffer.html#method.view).
+///
+/// Coordinates are relative to the view's top-left corner, and all operations are clipped to the view.
+#[derive(Debug)]
+pub struct CellView<'b> {
+	buffer: &'b mut CellBuffer,
+	area: Rect,
+}
+
+
+impl Cell {
+	/// Construct a cell from its character and colours.
+	pub fn new(ch: char, foreground: Color, background: Color) -> Cell {
+		Cell{
+			ch: ch,
+			foreground: foreground,
+			background: background,
+		}
+	}
+}
+
+impl CellBuffer {
+	/// Create an empty buffer of the specified size.
+	pub fn new(size: Size) -> CellBuffer {
+		CellBuffer{
+			size: size,
+			cells: vec![None; (size.width * size.height) as usize],
+		}
+	}
+
+	/// Get the buffer's size.
+	pub fn size(&self) -> Size {
+		self.size
+	}
+
+	/// Get the cell at the specified point, `None` if it's empty or out of bounds.
+	pub fn get(&self, point: Point) -> Option<&Cell> {
+		self.index(point).and_then(|idx| self.cells[idx].as_ref())
+	}
+
+	/// Get a view into the specified part of the buffer, clipped to the buffer's bounds.
+	pub fn view(&mut self, area: Rect) -> CellView<'_> {
+		let full = Rect::from_size(Point::new(0, 0), self.size);
+		CellView{
+			buffer: self,
+			area: clip(full, area),
+		}
+	}
+
+	/// Set the cell at the specified point.
+	pub fn put(&mut self, point: Point, cell: Cell) {
+		self.full_view().put(point, cell);
+	}
+
+	/// Put the characters of the specified string in consecutive cells, starting at the specified point.
+	///
+	/// Returns the amount of cells written.
+	pub fn print(&mut self, point: Point, text: &str, foreground: Color, background: Color) -> i32 {
+		self.full_view().print(point, text, foreground, background)
+	}
+
+	/// Set all cells in the specified area.
+	pub fn fill(&mut self, area: Rect, cell: Cell) {
+		self.full_view().fill(area, cell);
+	}
+
+	/// Empty the specified area or, if `None`, the whole buffer.
+	pub fn clear(&mut self, area: Option<Rect>) {
+		self.full_view().clear(area);
+	}
+
+	fn full_view(&mut self) -> CellView<'_> {
+		let size = self.size;
+		self.view(Rect::from_size(Point::new(0, 0), size))
+	}
+
+	fn index(&self, point: Point) -> Option<usize> {
+		if point.x >= 0 && point.y >= 0 && point.x < self.size.width && point.y < self.size.height {
+			Some((point.y * self.size.width + point.x) as usize)
+		} else {
+			None
+		}
+	}
+}
+
+impl<'b> CellView<'b> {
+	/// Get the area of the underlying buffer this view covers.
+	pub fn area(&self) -> Rect {
+		self.area
+	}
+
+	/// Get a view into the specified part of this view, clipped to this view's bounds.
+	pub fn view(&mut self, area: Rect) -> CellView<'_> {
+		let area = Rect::from_size(Point{x: self.area.top_left.x + area.top_left.x, y: self.area.top_left.y + area.top_left.y}, area.size);
+		CellView{
+			area: clip(self.area, area),
+			buffer: self.buffer,
+		}
+	}
+
+	/// Set the cell at the specified point.
+	pub fn put(&mut self, point: Point, cell: Cell) {
+		if let Some(idx) = self.index(point) {
+			self.buffer.cells[idx] = Some(cell);
+		}
+	}
+
+	/// Put the characters of the specified string in consecutive cells, starting at the specified point.
+	///
+	/// Returns the amount of cells written.
+	pub fn print(&mut self, point: Point, text: &str, foreground: Color, background: Color) -> i32 {
+		let mut written = 0;
+		for (i, ch) in text.chars().enumerate() {
+			let at = Point{x: point.x + i as i32, y: point.y};
+			if self.index(at).is_some() {
+				self.put(at, Cell::new(ch, foreground, background));
+				written += 1;
+			}
+		}
+		written
+	}
+
+	/// Set all cells in the specified area.
+	pub fn fill(&mut self, area: Rect, cell: Cell) {
+		self.set_area(area, Some(cell));
+	}
+
+	/// Empty the specified area or, if `None`, the whole view.
+	pub fn clear(&mut self, area: Option<Rect>) {
+		let area = area.unwrap_or_else(|| Rect::from_size(Point::new(0, 0), self.area.size));
+		self.set_area(area, None);
+	}
+
+	fn set_area(&mut self, area: Rect, cell: Option<Cell>) {
+		for y in area.top_left.y..area.bottom_left.y {
+			for x in area.top_left.x..area.top_right.x {
+				if let Some(idx) = self.index(Point{x: x, y: y}) {
+					self.buffer.cells[idx] = cell;
+				}
+			}
+		}
+	}
+
+	fn index(&self, point: Point) -> Option<usize> {
+		if point.x >= 0 && point.y >= 0 && point.x < self.area.size.width && point.y < self.area.size.height {
+			self.buffer.index(Point{x: self.area.top_left.x + point.x, y: self.area.top_left.y + point.y})
+		} else {
+			None
+		}
+	}
+}
+
+
+/// Draws frames composed in per-layer [`CellBuffer`](struct.CellBuffer.html)s, emitting only the cells that changed since
+/// the previous [`refresh()`](#method.refresh).
+///
+/// Layers are expected to have composition disabled, as changed cells are overwritten in place.
+///
+/// # Examples
+///
+/// ```
+/// use bear_lib_terminal::Color;
+/// use bear_lib_terminal::terminal;
+/// use bear_lib_terminal::terminal::buffer::{Cell, Renderer};
+/// use bear_lib_terminal::terminal::backend::Headless;
+/// use bear_lib_terminal::geometry::{Point, Rect, Size};
+///
+/// let backend = Headless::new();
+/// terminal::open_with(backend.clone(), "renderer example", 8, 2).unwrap();
+///
+/// let grass = Cell::new('"', Color::from_rgb(0x00, 0xC0, 0x00), Color::from_rgb(0x00, 0x00, 0x00));
+/// let hero = Cell::new('@', Color::from_rgb(0xFF, 0xFF, 0xFF), Color::from_rgb(0x00, 0x00, 0x00));
+///
+/// let mut renderer = Renderer::new(Size::new(8, 2));
+/// renderer.layer(0).fill(Rect::from_values(0, 0, 8, 2), grass);
+/// renderer.layer(1).put(Point::new(2, 1), hero);
+/// let stats = renderer.refresh();
+/// assert_eq!((stats.cells_drawn, stats.layers_redrawn), (17, 2));
+/// assert_eq!(backend.line(1, 1), "  @     ");
+///
+/// renderer.layer(1).clear(None);
+/// renderer.layer(1).put(Point::new(3, 1), hero);
+/// let stats = renderer.refresh();
+/// assert_eq!((stats.cells_drawn, stats.cells_cleared, stats.layers_redrawn), (1, 1, 0));
+/// assert_eq!(backend.line(1, 1), "   @    ");
+/// terminal::close();
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Renderer {
+	size: Size,
+	layers: BTreeMap<i32, Frame>,
+}
+
+/// What a [`Renderer::refresh()`](struct.Renderer.html#method.refresh) had to do to bring the terminal up to date.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct RenderStats {
+	/// Amount of cells put onto the terminal.
+	pub cells_drawn: usize,
+	/// Amount of cells that became empty and were cleared.
+	pub cells_cleared: usize,
+	/// Amount of foreground and background colour changes.
+	pub color_switches: usize,
+	/// Amount of layers drawn from scratch, because they had no previous frame.
+	pub layers_redrawn: usize,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Frame {
+	current: CellBuffer,
+	previous: Option<CellBuffer>,
+}
+
+
+impl Renderer {
+	/// Create a renderer for frames of the specified size, placed at the terminal's top-left corner.
+	pub fn new(size: Size) -> Renderer {
+		Renderer{
+			size: size,
+			layers: BTreeMap::new(),
+		}
+	}
+
+	/// Get the size of the frames.
+	pub fn size(&self) -> Size {
+		self.size
+	}
+
+	/// Get the frame being composed for the specified layer, creating an empty one if it doesn't exist yet.
+	///
+	/// The frame keeps its contents across refreshes, so only the parts that change need to be redrawn.
+	pub fn layer(&mut self, index: i32) -> &mut CellBuffer {
+		let size = self.size;
+		&mut self.layers.entry(index).or_insert_with(|| Frame{
+			current: CellBuffer::new(size),
+			previous: None,
+		}).current
+	}
+
+	/// Forget what's on the terminal, so the next [`refresh()`](#method.refresh) redraws every layer from scratch.
+	///
+	/// Use this after something other than this renderer drew onto its layers.
+	pub fn invalidate(&mut self) {
+		for frame in self.layers.values_mut() {
+			frame.previous = None;
+		}
+	}
+
+	/// Draw the differences between the composed and previous frames onto the terminal, then [`terminal::refresh()`](../fn.refresh.html) it.
+	pub fn refresh(&mut self) -> RenderStats {
+		let mut stats = RenderStats::default();
+
+		for (&index, frame) in &mut self.layers {
+			let mut pen = Pen::new(index);
+
+			if frame.previous.is_none() {
+				stats.layers_redrawn += 1;
+				terminal::clear(Some(Rect::from_size(Point::new(0, 0), self.size)));
+			}
+			for y in 0..self.size.height {
+				for x in 0..self.size.width {
+					let point = Point::new(x, y);
+					let previous = frame.previous.as_ref().and_then(|previous| previous.get(point));
+					match (previous, frame.current.get(point)) {
+						(previous, Some(cell)) if previous != Some(cell) => {
+							pen.put(x, y, cell);
+							stats.cells_drawn += 1;
+						},
+						(Some(_), None) => {
+							pen.clear(x, y);
+							stats.cells_cleared += 1;
+						},
+						_ => {},
+					}
+				}
+			}
+
+			stats.color_switches += pen.finish();
+			frame.previous = Some(frame.current.clone());
+		}
+
+		terminal::refresh();
+		stats
+	}
+}
+
+
+/// Draw the buffer's non-empty cells onto the specified layer of the terminal, with the buffer's top-left corner at the specified point.
+///
+/// The foreground and background colours are only switched when they change between consecutive cells.
+///
+/// The current layer and colours are restored afterwards.
+pub fn blit(buffer: &CellBuffer, at: Point, layer: i32) {
+	let mut pen = Pen::new(layer);
+	for y in 0..buffer.size.height {
+		for x in 0..buffer.size.width {
+			if let Some(cell) = buffer.get(Point::new(x, y)) {
+				pen.put(at.x + x, at.y + y, cell);
+			}
+		}
+	}
+	pen.finish();
+}
+
+
+/// Draws cells onto a single layer, switching colours only when needed and restoring the previous state when done.
+struct Pen {
+	previous_layer: i32,
+	previous_foreground: Color,
+	previous_background: Color,
+	foreground: Color,
+	background: Color,
+	color_switches: usize,
+}
+
+impl Pen {
+	fn new(layer: i32) -> Pen {
+		let previous_layer = state::layer();
+		let foreground = state::foreground();
+		let background = state::background();
+		terminal::layer(layer);
+
+		Pen{
+			previous_layer: previous_layer,
+			previous_foreground: foreground,
+			previous_background: background,
+			foreground: foreground,
+			background: background,
+			color_switches: 0,
+		}
+	}
+
+	fn put(&mut self, x: i32, y: i32, cell: &Cell) {
+		if cell.foreground != self.foreground {
+			self.foreground = cell.foreground;
+			self.color_switches += 1;
+			terminal::set_foreground(self.foreground);
+		}
+		if cell.background != self.background {
+			self.background = cell.background;
+			self.color_switches += 1;
+			terminal::set_background(self.background);
+		}
+		terminal::put_xy(x, y, cell.ch);
+	}
+
+	/// Clear the cell with the previous background, rather than the last one put, which clearing layer 0 would leave behind.
+	fn clear(&mut self, x: i32, y: i32) {
+		if self.background != self.previous_background {
+			self.background = self.previous_background;
+			self.color_switches += 1;
+			terminal::set_background(self.background);
+		}
+		terminal::clear(Some(Rect::from_values(x, y, 1, 1)));
+	}
+
+	/// Restore the previous layer and colours, returning the amount of colour switches made.
+	fn finish(self) -> usize {
+		if self.foreground != self.previous_foreground {
+			terminal::set_foreground(self.previous_foreground);
+		}
+		if self.background != self.previous_background {
+			terminal::set_background(self.previous_background);
+		}
+		terminal::layer(self.previous_layer);
+		self.color_switches
+	}
+}
+
+
+/// Intersect two `Rect`s, yielding an empty one if they don't overlap.
+fn clip(bounds: Rect, area: Rect) -> Rect {
+	let left   = cmp::max(bounds.top_left.x, area.top_left.x);
+	let top    = cmp::max(bounds.top_left.y, area.top_left.y);
+	let right  = cmp::max(left, cmp::min(bounds.bottom_right.x, area.bottom_right.x));
+	let bottom = cmp::max(top, cmp::min(bounds.bottom_right.y, area.bottom_right.y));
+	Rect::from_point_values(left, top, right, bottom)
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::{Cell, Renderer};
+	use colors::Color;
+	use geometry::{Point, Rect, Size};
+	use terminal::{self, state};
+	use terminal::backend::Headless;
+
+
+	fn cell(ch: char, background: Color) -> Cell {
+		Cell::new(ch, Color::from_rgb(0xFF, 0xFF, 0xFF), background)
+	}
+
+
+	#[test]
+	fn unchanged_frames_draw_nothing() {
+		let backend = Headless::new();
+		terminal::open_with(backend.clone(), "unchanged_frames_draw_nothing", 4, 2).unwrap();
+		let mut renderer = Renderer::new(Size::new(4, 2));
+		renderer.layer(0).fill(Rect::from_values(0, 0, 4, 2), cell('.', state::background()));
+		assert_eq!(renderer.refresh().cells_drawn, 8);
+
+		let stats = renderer.refresh();
+		assert_eq!((stats.cells_drawn, stats.cells_cleared, stats.color_switches, stats.layers_redrawn), (0, 0, 0, 0));
+		assert_eq!(backend.refreshes(), 2);
+
+		renderer.invalidate();
+		assert_eq!(renderer.refresh().layers_redrawn, 1);
+		terminal::close();
+	}
+
+	#[test]
+	fn vacated_cells_get_outside_background() {
+		let sky = Color::from_rgb(0x00, 0x80, 0xFF);
+		terminal::open_with(Headless::new(), "vacated_cells_get_outside_background", 4, 2).unwrap();
+		let mut renderer = Renderer::new(Size::new(4, 2));
+		renderer.layer(0).fill(Rect::from_values(0, 0, 2, 1), cell(' ', sky));
+		renderer.refresh();
+
+		renderer.layer(0).clear(None);
+		renderer.layer(0).put(Point::new(0, 0), cell(' ', sky));
+		assert_eq!(renderer.refresh().cells_cleared, 1);
+		assert_eq!(terminal::pick_background_color(Point::new(0, 0)), Ok(sky));
+		assert_eq!(terminal::pick_background_color(Point::new(1, 0)), Ok(state::background()));
+		assert_eq!((state::layer(), state::foreground()), (0, Color::from_rgb(0xFF, 0xFF, 0xFF)));
+		terminal::close();
+	}
+}
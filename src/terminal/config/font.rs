@@ -17,15 +17,17 @@
 //! use bear_lib_terminal::geometry::Size;
 //! # assert!({let result =
 //! terminal::set(font::true_type(font::Origin::Root, "UbuntuMono-R.ttf", Size::new(0, 12)));
-//! # result}); assert!({let result =
+//! # result.is_ok()}); assert!({let result =
 //! terminal::set(font::bitmap(font::Origin::Offset('^'), "circumflex.png"));
-//! # result}); assert!({let result =
+//! # result.is_ok()}); assert!({let result =
 //! terminal::set(font::bitmap(font::Origin::Offset('\u{E000}'), "tileset.png").size(Size::new(16, 16)).spacing(Size::new(2, 1)));
-//! # result});
+//! # result.is_ok()});
 //! ```
 
 
 use std::fmt;
+use std::char;
+use std::str::FromStr;
 use std::path::Path;
 use std::borrow::Cow;
 use geometry::Size;
@@ -300,6 +302,24 @@ impl fmt::Display for Origin {
 	}
 }
 
+/// Parses `font` (or `[font]`), `<name> font` and `0xNNNN` (or `U+NNNN`).
+impl FromStr for Origin {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Origin, String> {
+		let s = s.trim();
+		if s == "font" || s == "[font]" {
+			Ok(Origin::Root)
+		} else if let Some(name) = s.strip_suffix(" font") {
+			Ok(Origin::Named(Cow::Owned(name.trim().to_string())))
+		} else if let Some(hex) = ["0x", "0X", "U+", "u+"].iter().filter_map(|prefix| s.strip_prefix(prefix)).next() {
+			u32::from_str_radix(hex, 16).ok().and_then(char::from_u32).map(Origin::Offset).ok_or_else(|| format!("invalid font offset \"{}\"", s))
+		} else {
+			Err(format!("unknown font origin \"{}\"", s))
+		}
+	}
+}
+
 impl fmt::Display for RasterizationMode {
 	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
 		match self {
@@ -310,6 +330,19 @@ impl fmt::Display for RasterizationMode {
 	}
 }
 
+impl FromStr for RasterizationMode {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<RasterizationMode, String> {
+		match s {
+			"monochrome" => Ok(RasterizationMode::Monochrome),
+			"normal"     => Ok(RasterizationMode::Normal),
+			"lcd"        => Ok(RasterizationMode::Lcd),
+			_            => Err(format!("unknown rasterization mode \"{}\"", s)),
+		}
+	}
+}
+
 impl fmt::Display for ResizeFilter {
 	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
 		match self {
@@ -320,6 +353,19 @@ impl fmt::Display for ResizeFilter {
 	}
 }
 
+impl FromStr for ResizeFilter {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<ResizeFilter, String> {
+		match s {
+			"nearest"  => Ok(ResizeFilter::Nearest),
+			"bilinear" => Ok(ResizeFilter::Bilinear),
+			"bicubic"  => Ok(ResizeFilter::Bicubic),
+			_          => Err(format!("unknown resize filter \"{}\"", s)),
+		}
+	}
+}
+
 impl fmt::Display for ResizeMode {
 	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
 		match self {
@@ -330,6 +376,19 @@ impl fmt::Display for ResizeMode {
 	}
 }
 
+impl FromStr for ResizeMode {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<ResizeMode, String> {
+		match s {
+			"stretch" => Ok(ResizeMode::Stretch),
+			"fit"     => Ok(ResizeMode::Fit),
+			"crop"    => Ok(ResizeMode::Crop),
+			_         => Err(format!("unknown resize mode \"{}\"", s)),
+		}
+	}
+}
+
 impl fmt::Display for Align {
 	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
 		match self {
@@ -341,3 +400,18 @@ impl fmt::Display for Align {
 		}
 	}
 }
+
+impl FromStr for Align {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Align, String> {
+		match s {
+			"center"       => Ok(Align::Center),
+			"top-left"     => Ok(Align::TopLeft),
+			"bottom-left"  => Ok(Align::BottomLeft),
+			"top-right"    => Ok(Align::TopRight),
+			"bottom-right" => Ok(Align::BottomRight),
+			_              => Err(format!("unknown tile alignment \"{}\"", s)),
+		}
+	}
+}
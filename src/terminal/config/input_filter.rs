@@ -1,4 +1,5 @@
 use std::fmt;
+use std::str::FromStr;
 use terminal::config::{ConfigPart, escape_config_string};
 
 
@@ -168,6 +169,31 @@ impl ConfigPart for Vec<InputFilter> {
 }
 
 
+/// Parses a single filter element, as in `'keyboard+'`.
+///
+/// Group names take precedence over event names, and anything else alphanumeric is taken as [`Alnum`](#variant.Alnum) keys.
+impl FromStr for InputFilter {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<InputFilter, String> {
+		let (name, both) = match s.trim().strip_suffix('+') {
+			Some(name) => (name, true),
+			None       => (s.trim(), false),
+		};
+
+		if let Ok(group) = name.parse() {
+			Ok(InputFilter::Group{group: group, both: both})
+		} else if let Ok(event) = name.parse() {
+			Ok(InputFilter::Event{name: event, both: both})
+		} else if !name.is_empty() && name.chars().all(char::is_alphanumeric) {
+			Ok(InputFilter::Alnum{keys: name.to_string(), both: both})
+		} else {
+			Err(format!("invalid input filter \"{}\"", s))
+		}
+	}
+}
+
+
 impl fmt::Display for InputFilterGroup {
 	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
 		formatter.write_str(match self {
@@ -180,6 +206,21 @@ impl fmt::Display for InputFilterGroup {
 	}
 }
 
+impl FromStr for InputFilterGroup {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<InputFilterGroup, String> {
+		match s {
+			"arrow"    => Ok(InputFilterGroup::Arrow),
+			"keypad"   => Ok(InputFilterGroup::Keypad),
+			"keyboard" => Ok(InputFilterGroup::Keyboard),
+			"mouse"    => Ok(InputFilterGroup::Mouse),
+			"system"   => Ok(InputFilterGroup::System),
+			_          => Err(format!("unknown input filter group \"{}\"", s)),
+		}
+	}
+}
+
 impl fmt::Display for InputFilterEvent {
 	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
 		formatter.write_str(match self {
@@ -306,3 +347,133 @@ impl fmt::Display for InputFilterEvent {
 		})
 	}
 }
+
+impl FromStr for InputFilterEvent {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<InputFilterEvent, String> {
+		match s {
+			"A"            => Ok(InputFilterEvent::A),
+			"B"            => Ok(InputFilterEvent::B),
+			"C"            => Ok(InputFilterEvent::C),
+			"D"            => Ok(InputFilterEvent::D),
+			"E"            => Ok(InputFilterEvent::E),
+			"F"            => Ok(InputFilterEvent::F),
+			"G"            => Ok(InputFilterEvent::G),
+			"H"            => Ok(InputFilterEvent::H),
+			"I"            => Ok(InputFilterEvent::I),
+			"J"            => Ok(InputFilterEvent::J),
+			"K"            => Ok(InputFilterEvent::K),
+			"L"            => Ok(InputFilterEvent::L),
+			"M"            => Ok(InputFilterEvent::M),
+			"N"            => Ok(InputFilterEvent::N),
+			"O"            => Ok(InputFilterEvent::O),
+			"P"            => Ok(InputFilterEvent::P),
+			"Q"            => Ok(InputFilterEvent::Q),
+			"R"            => Ok(InputFilterEvent::R),
+			"S"            => Ok(InputFilterEvent::S),
+			"T"            => Ok(InputFilterEvent::T),
+			"U"            => Ok(InputFilterEvent::U),
+			"V"            => Ok(InputFilterEvent::V),
+			"W"            => Ok(InputFilterEvent::W),
+			"X"            => Ok(InputFilterEvent::X),
+			"Y"            => Ok(InputFilterEvent::Y),
+			"Z"            => Ok(InputFilterEvent::Z),
+			"0"            => Ok(InputFilterEvent::Row0),
+			"1"            => Ok(InputFilterEvent::Row1),
+			"2"            => Ok(InputFilterEvent::Row2),
+			"3"            => Ok(InputFilterEvent::Row3),
+			"4"            => Ok(InputFilterEvent::Row4),
+			"5"            => Ok(InputFilterEvent::Row5),
+			"6"            => Ok(InputFilterEvent::Row6),
+			"7"            => Ok(InputFilterEvent::Row7),
+			"8"            => Ok(InputFilterEvent::Row8),
+			"9"            => Ok(InputFilterEvent::Row9),
+			"space"        => Ok(InputFilterEvent::Space),
+			"minus"        => Ok(InputFilterEvent::Minus),
+			"equals"       => Ok(InputFilterEvent::Equals),
+			"lbracket"     => Ok(InputFilterEvent::LBracket),
+			"rbracket"     => Ok(InputFilterEvent::RBracket),
+			"backslash"    => Ok(InputFilterEvent::Backslash),
+			"semicolon"    => Ok(InputFilterEvent::Semicolon),
+			"apostrophe"   => Ok(InputFilterEvent::Apostrophe),
+			"grave"        => Ok(InputFilterEvent::Grave),
+			"comma"        => Ok(InputFilterEvent::Comma),
+			"period"       => Ok(InputFilterEvent::Period),
+			"slash"        => Ok(InputFilterEvent::Slash),
+			"F1"           => Ok(InputFilterEvent::F1),
+			"F2"           => Ok(InputFilterEvent::F2),
+			"F3"           => Ok(InputFilterEvent::F3),
+			"F4"           => Ok(InputFilterEvent::F4),
+			"F5"           => Ok(InputFilterEvent::F5),
+			"F6"           => Ok(InputFilterEvent::F6),
+			"F7"           => Ok(InputFilterEvent::F7),
+			"F8"           => Ok(InputFilterEvent::F8),
+			"F9"           => Ok(InputFilterEvent::F9),
+			"F10"          => Ok(InputFilterEvent::F10),
+			"F11"          => Ok(InputFilterEvent::F11),
+			"F12"          => Ok(InputFilterEvent::F12),
+			"return"       => Ok(InputFilterEvent::Return),
+			"escape"       => Ok(InputFilterEvent::Escape),
+			"backspace"    => Ok(InputFilterEvent::Backspace),
+			"tab"          => Ok(InputFilterEvent::Tab),
+			"pause"        => Ok(InputFilterEvent::Pause),
+			"insert"       => Ok(InputFilterEvent::Insert),
+			"home"         => Ok(InputFilterEvent::Home),
+			"pageup"       => Ok(InputFilterEvent::Pageup),
+			"delete"       => Ok(InputFilterEvent::Delete),
+			"end"          => Ok(InputFilterEvent::End),
+			"pagedown"     => Ok(InputFilterEvent::Pagedown),
+			"right"        => Ok(InputFilterEvent::Right),
+			"left"         => Ok(InputFilterEvent::Left),
+			"down"         => Ok(InputFilterEvent::Down),
+			"up"           => Ok(InputFilterEvent::Up),
+			"shift"        => Ok(InputFilterEvent::Shift),
+			"control"      => Ok(InputFilterEvent::Control),
+			"KP_0"         => Ok(InputFilterEvent::Pad0),
+			"KP_1"         => Ok(InputFilterEvent::Pad1),
+			"KP_2"         => Ok(InputFilterEvent::Pad2),
+			"KP_3"         => Ok(InputFilterEvent::Pad3),
+			"KP_4"         => Ok(InputFilterEvent::Pad4),
+			"KP_5"         => Ok(InputFilterEvent::Pad5),
+			"KP_6"         => Ok(InputFilterEvent::Pad6),
+			"KP_7"         => Ok(InputFilterEvent::Pad7),
+			"KP_8"         => Ok(InputFilterEvent::Pad8),
+			"KP_9"         => Ok(InputFilterEvent::Pad9),
+			"KP_divide"    => Ok(InputFilterEvent::PadDivide),
+			"KP_multiply"  => Ok(InputFilterEvent::PadMultiply),
+			"KP_minus"     => Ok(InputFilterEvent::PadMinus),
+			"KP_plus"      => Ok(InputFilterEvent::PadPlus),
+			"KP_period"    => Ok(InputFilterEvent::PadPeriod),
+			"KP_enter"     => Ok(InputFilterEvent::PadEnter),
+			"mouse_left"   => Ok(InputFilterEvent::MouseLeft),
+			"mouse_right"  => Ok(InputFilterEvent::MouseRight),
+			"mouse_middle" => Ok(InputFilterEvent::MouseMiddle),
+			"mouse_x1"     => Ok(InputFilterEvent::MouseX1),
+			"mouse_x2"     => Ok(InputFilterEvent::MouseX2),
+			"mouse_move"   => Ok(InputFilterEvent::MouseMove),
+			"mouse_scroll" => Ok(InputFilterEvent::MouseScroll),
+			"mouse_wheel"  => Ok(InputFilterEvent::MouseWheel),
+			"mouse_x"      => Ok(InputFilterEvent::MouseX),
+			"mouse_y"      => Ok(InputFilterEvent::MouseY),
+			"mouse_pixelx" => Ok(InputFilterEvent::MousePixelX),
+			"mouse_pixely" => Ok(InputFilterEvent::MousePixelY),
+			"mouse_clicks" => Ok(InputFilterEvent::MouseClicks),
+			"width"        => Ok(InputFilterEvent::Width),
+			"height"       => Ok(InputFilterEvent::Height),
+			"cell_width"   => Ok(InputFilterEvent::CellWidth),
+			"cell_height"  => Ok(InputFilterEvent::CellHeight),
+			"color"        => Ok(InputFilterEvent::Color),
+			"bkcolor"      => Ok(InputFilterEvent::Bkcolor),
+			"layer"        => Ok(InputFilterEvent::Layer),
+			"composition"  => Ok(InputFilterEvent::Composition),
+			"char"         => Ok(InputFilterEvent::Char),
+			"wchar"        => Ok(InputFilterEvent::Wchar),
+			"event"        => Ok(InputFilterEvent::Event),
+			"fullscreen"   => Ok(InputFilterEvent::Fullscreen),
+			"close"        => Ok(InputFilterEvent::Close),
+			"resized"      => Ok(InputFilterEvent::Resized),
+			_              => Err(format!("unknown input filter event \"{}\"", s)),
+		}
+	}
+}
@@ -14,12 +14,15 @@
 //! use bear_lib_terminal::geometry::Size;
 //! # assert!({let result =
 //! terminal::set(config::Window::empty().title("foobar".to_string()).size(Size::new(80, 25)));
-//! # result}); assert!({let result =
+//! # result.is_ok()}); assert!({let result =
 //! terminal::set(vec![config::InputFilter::Group{group: config::InputFilterGroup::Keyboard, both: false}]);
-//! # result});
+//! # result.is_ok()});
 //! ```
+//!
+//! Configuration strings can also be parsed back with [`parse()`](fn.parse.html) or the sections' `FromStr` implementations.
 
 
+mod parse;
 mod section;
 mod input_filter;
 
@@ -27,6 +30,7 @@ pub mod font;
 
 pub use self::section::*;
 pub use self::input_filter::*;
+pub use self::parse::{parse, parse_input_filter, ParseError, Section};
 
 
 /// Trait for generating BLT configuration strings.
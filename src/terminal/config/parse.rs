@@ -0,0 +1,617 @@
+use std::fmt;
+use std::char;
+use std::error::Error;
+use std::str::FromStr;
+use geometry::Size;
+use terminal::config::{Cellsize, ConfigPart, Input, InputFilter, Log, Output, Terminal, Window};
+use terminal::config::font::{self, Bitmap, Origin, TrueType};
+
+
+/// Parse a configuration string, as accepted by [`terminal::set()`](../fn.set.html), into its sections.
+///
+/// Both the `section: property=value, ...` and `section.property=value` forms are supported, separated by `;`s.
+/// Font sections are parsed into a [`TrueType`](font/struct.TrueType.html) font for `.ttf` and `.otf` files,
+/// and into a [`Bitmap`](font/struct.Bitmap.html) one otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use bear_lib_terminal::terminal::config::{self, ConfigPart, InputFilter, InputFilterGroup, Section};
+/// use bear_lib_terminal::geometry::Size;
+///
+/// let sections = config::parse("window: title='foobar', size=80x25; input.filter=[keyboard, mouse+]").unwrap();
+/// assert_eq!(sections,
+///            vec![Section::Window(config::Window::empty().title("foobar".to_string()).size(Size::new(80, 25))),
+///                 Section::InputFilter(vec![InputFilter::Group{group: InputFilterGroup::Keyboard, both: false},
+///                                           InputFilter::Group{group: InputFilterGroup::Mouse, both: true}])]);
+///
+/// let font = "0xE000: 'tileset.png', size=16x16, spacing=2x1;";
+/// assert_eq!(config::parse(font).unwrap()[0].to_config_str(), font);
+/// ```
+pub fn parse(config: &str) -> Result<Vec<Section>, ParseError> {
+	let mut sections = vec![];
+	for statement in statements(config)? {
+		let section = match &statement.section[..] {
+			"terminal" => Section::Terminal(terminal(statement.properties)?),
+			"window"   => Section::Window(window(statement.properties)?),
+			"output"   => Section::Output(output(statement.properties)?),
+			"log"      => Section::Log(log(statement.properties)?),
+			"input"    => {
+				let (filter, properties): (Vec<_>, Vec<_>) = statement.properties.into_iter().partition(|property| property.name == "filter");
+				if let Some(filter) = filter.into_iter().last() {
+					sections.push(Section::InputFilter(input_filter(&filter.value)?));
+				}
+				if properties.is_empty() {
+					continue;
+				}
+				Section::Input(input(properties)?)
+			},
+			_          => {
+				let origin = statement.section.parse().map_err(|_| ParseError::UnknownSection(statement.section.clone()))?;
+				let path = statement.properties.iter().find(|property| property.name.is_empty()).map(|property| property.value.to_lowercase());
+				match path {
+					Some(ref path) if path.ends_with(".ttf") || path.ends_with(".otf") => Section::TrueType(true_type(origin, statement.properties)?),
+					_                                                                   => Section::Bitmap(bitmap(origin, statement.properties)?),
+				}
+			},
+		};
+		sections.push(section);
+	}
+	Ok(sections)
+}
+
+/// Parse the value of the `input.filter` property, e.g. `[keyboard, 'A+']`, or a whole `input.filter=...` configuration string.
+///
+/// # Examples
+///
+/// ```
+/// use bear_lib_terminal::terminal::config::{self, ConfigPart, InputFilter, InputFilterEvent};
+///
+/// let filter = vec![InputFilter::Event{name: InputFilterEvent::Escape, both: false}, InputFilter::Alnum{keys: "wasd".to_string(), both: true}];
+/// assert_eq!(config::parse_input_filter("escape, wasd+").unwrap(), filter);
+/// assert_eq!(config::parse_input_filter(&filter.to_config_str()).unwrap(), filter);
+/// ```
+pub fn parse_input_filter(filter: &str) -> Result<Vec<InputFilter>, ParseError> {
+	if split(filter, '=')?.len() == 1 {
+		return input_filter(filter);
+	}
+
+	match single_section(filter, "input")?.into_iter().find(|property| property.name == "filter") {
+		Some(property) => input_filter(&property.value),
+		None           => Err(ParseError::MissingProperty{section: "input".to_string(), property: "filter".to_string()}),
+	}
+}
+
+
+/// A single section of a configuration string, as parsed by [`parse()`](fn.parse.html).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Section {
+	Terminal(Terminal),
+	Window(Window),
+	Input(Input),
+	InputFilter(Vec<InputFilter>),
+	Output(Output),
+	Log(Log),
+	Bitmap(Bitmap),
+	TrueType(TrueType),
+}
+
+/// Reasons a configuration string couldn't be parsed.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ParseError {
+	/// A part of the string doesn't fit the configuration string format, e.g. has an unterminated `'`.
+	Malformed(String),
+	/// A section other than the one being parsed, or one not known at all.
+	UnknownSection(String),
+	/// A property the section doesn't have.
+	UnknownProperty{section: String, property: String},
+	/// A required property wasn't specified, like a font's file.
+	MissingProperty{section: String, property: String},
+	/// A property's value couldn't be parsed.
+	InvalidValue{property: String, value: String},
+}
+
+impl ConfigPart for Section {
+	fn to_config_str(&self) -> String {
+		match *self {
+			Section::Terminal(ref section)    => section.to_config_str(),
+			Section::Window(ref section)      => section.to_config_str(),
+			Section::Input(ref section)       => section.to_config_str(),
+			Section::InputFilter(ref section) => section.to_config_str(),
+			Section::Output(ref section)      => section.to_config_str(),
+			Section::Log(ref section)         => section.to_config_str(),
+			Section::Bitmap(ref section)      => section.to_config_str(),
+			Section::TrueType(ref section)    => section.to_config_str(),
+		}
+	}
+}
+
+impl fmt::Display for ParseError {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			ParseError::Malformed(ref part)                        => write!(formatter, "malformed configuration \"{}\"", part),
+			ParseError::UnknownSection(ref section)                => write!(formatter, "unknown section \"{}\"", section),
+			ParseError::UnknownProperty{ref section, ref property} => write!(formatter, "unknown property \"{}\" in section \"{}\"", property, section),
+			ParseError::MissingProperty{ref section, ref property} => write!(formatter, "section \"{}\" requires property \"{}\"", section, property),
+			ParseError::InvalidValue{ref property, ref value}      => write!(formatter, "invalid value \"{}\" for property \"{}\"", value, property),
+		}
+	}
+}
+
+impl Error for ParseError {}
+
+
+impl FromStr for Terminal {
+	type Err = ParseError;
+
+	fn from_str(s: &str) -> Result<Terminal, ParseError> {
+		terminal(single_section(s, "terminal")?)
+	}
+}
+
+/// Parses `window` sections, the inverse of [`to_config_str()`](trait.ConfigPart.html#tymethod.to_config_str).
+///
+/// # Examples
+///
+/// ```
+/// use bear_lib_terminal::terminal::config::{ConfigPart, Window};
+/// use bear_lib_terminal::geometry::Size;
+///
+/// let window = Window::empty().size(Size::new(80, 25)).title("It's a 'game'".to_string());
+/// assert_eq!(window.to_config_str().parse::<Window>().unwrap(), window);
+/// assert_eq!("window.resizeable=true".parse::<Window>().unwrap(), Window::empty().resizeable(true));
+/// ```
+impl FromStr for Window {
+	type Err = ParseError;
+
+	fn from_str(s: &str) -> Result<Window, ParseError> {
+		window(single_section(s, "window")?)
+	}
+}
+
+impl FromStr for Input {
+	type Err = ParseError;
+
+	fn from_str(s: &str) -> Result<Input, ParseError> {
+		input(single_section(s, "input")?)
+	}
+}
+
+impl FromStr for Output {
+	type Err = ParseError;
+
+	fn from_str(s: &str) -> Result<Output, ParseError> {
+		output(single_section(s, "output")?)
+	}
+}
+
+impl FromStr for Log {
+	type Err = ParseError;
+
+	fn from_str(s: &str) -> Result<Log, ParseError> {
+		log(single_section(s, "log")?)
+	}
+}
+
+/// Parses bitmap font sections, the inverse of [`to_config_str()`](../trait.ConfigPart.html#tymethod.to_config_str).
+///
+/// # Examples
+///
+/// ```
+/// use bear_lib_terminal::terminal::config::ConfigPart;
+/// use bear_lib_terminal::terminal::config::font::{self, Bitmap, Origin};
+/// use bear_lib_terminal::geometry::Size;
+///
+/// let tileset = font::bitmap(Origin::Offset('\u{E000}'), "tileset.png").size(Size::new(16, 16));
+/// assert_eq!(tileset.to_config_str().parse::<Bitmap>().unwrap(), tileset);
+/// ```
+impl FromStr for Bitmap {
+	type Err = ParseError;
+
+	fn from_str(s: &str) -> Result<Bitmap, ParseError> {
+		let (origin, properties) = font_section(s)?;
+		bitmap(origin, properties)
+	}
+}
+
+/// Parses TrueType font sections, the inverse of [`to_config_str()`](../trait.ConfigPart.html#tymethod.to_config_str).
+///
+/// # Examples
+///
+/// ```
+/// use bear_lib_terminal::terminal::config::font::{self, Origin, TrueType};
+/// use bear_lib_terminal::geometry::Size;
+///
+/// assert_eq!("font: UbuntuMono-R.ttf, size=12".parse::<TrueType>().unwrap(),
+///            font::true_type(Origin::Root, "UbuntuMono-R.ttf", Size::new(0, 12)));
+/// ```
+impl FromStr for TrueType {
+	type Err = ParseError;
+
+	fn from_str(s: &str) -> Result<TrueType, ParseError> {
+		let (origin, properties) = font_section(s)?;
+		true_type(origin, properties)
+	}
+}
+
+
+struct Statement {
+	section: String,
+	properties: Vec<Property>,
+}
+
+/// A `name=value` pair, with an empty name for an unnamed value, like a font's file.
+struct Property {
+	name: String,
+	value: String,
+}
+
+
+fn terminal(properties: Vec<Property>) -> Result<Terminal, ParseError> {
+	let mut terminal = None;
+	for property in properties {
+		match &property.name[..] {
+			"encoding" => terminal = Some(Terminal::new(property.value)),
+			_          => return Err(unknown_property("terminal", property)),
+		}
+	}
+	terminal.ok_or_else(|| ParseError::MissingProperty{section: "terminal".to_string(), property: "encoding".to_string()})
+}
+
+fn window(properties: Vec<Property>) -> Result<Window, ParseError> {
+	let mut window = Window::empty();
+	for property in properties {
+		window = match &property.name[..] {
+			"size"       => window.size(size(&property)?),
+			"cellsize"   => window.cellsize(match &property.value[..] {
+				"auto" => Cellsize::Auto,
+				_      => Cellsize::Sized(size(&property)?),
+			}),
+			"title"      => window.title(property.value),
+			"icon"       => window.icon(property.value),
+			"resizeable" => window.resizeable(value(&property)?),
+			"fullscreen" => window.fullscreen(value(&property)?),
+			_            => return Err(unknown_property("window", property)),
+		};
+	}
+	Ok(window)
+}
+
+fn input(properties: Vec<Property>) -> Result<Input, ParseError> {
+	let mut input = Input::empty();
+	for property in properties {
+		input = match &property.name[..] {
+			"precise-mouse"     => input.precise_mouse(value(&property)?),
+			"mouse-cursor"      => input.mouse_cursor(value(&property)?),
+			"cursor-symbol"     => input.cursor_symbol(codepoint(&property)?),
+			"cursor-blink-rate" => input.cursor_blink_rate(value(&property)?),
+			_                   => return Err(unknown_property("input", property)),
+		};
+	}
+	Ok(input)
+}
+
+fn input_filter(value: &str) -> Result<Vec<InputFilter>, ParseError> {
+	let value = value.trim();
+	let value = value.strip_prefix('[').and_then(|value| value.strip_suffix(']')).unwrap_or(value);
+	split(value, ',')?.into_iter().map(str::trim).filter(|filter| !filter.is_empty()).map(|filter| unquote(filter).parse().map_err(|_| ParseError::InvalidValue{
+		property: "filter".to_string(),
+		value: filter.to_string(),
+	})).collect()
+}
+
+fn output(properties: Vec<Property>) -> Result<Output, ParseError> {
+	let mut output = Output::clean();
+	for property in properties {
+		output = match &property.name[..] {
+			"postformatting" => output.postformatting(value(&property)?),
+			"vsync"          => output.vsync(value(&property)?),
+			_                => return Err(unknown_property("output", property)),
+		};
+	}
+	Ok(output)
+}
+
+fn log(properties: Vec<Property>) -> Result<Log, ParseError> {
+	let mut log = Log::empty();
+	for property in properties {
+		log = match &property.name[..] {
+			"file"  => log.file(property.value),
+			"level" => log.level(value(&property)?),
+			"mode"  => log.mode(value(&property)?),
+			_       => return Err(unknown_property("log", property)),
+		};
+	}
+	Ok(log)
+}
+
+fn bitmap(origin: Origin, mut properties: Vec<Property>) -> Result<Bitmap, ParseError> {
+	let mut bitmap = font::bitmap(origin.clone(), font_path(&origin, &mut properties)?);
+	for property in properties {
+		bitmap = match &property.name[..] {
+			"size"          => bitmap.size(size(&property)?),
+			"resize"        => bitmap.resize(size(&property)?),
+			"resize-filter" => bitmap.resize_filter(value(&property)?),
+			"resize-mode"   => bitmap.resize_mode(value(&property)?),
+			"raw-size"      => bitmap.raw_size(size(&property)?),
+			"codepage"      => bitmap.codepage(property.value),
+			"align"         => bitmap.align(value(&property)?),
+			"spacing"       => bitmap.spacing(size(&property)?),
+			_               => return Err(unknown_property(&origin.to_string(), property)),
+		};
+	}
+	Ok(bitmap)
+}
+
+fn true_type(origin: Origin, mut properties: Vec<Property>) -> Result<TrueType, ParseError> {
+	let path = font_path(&origin, &mut properties)?;
+	let tile_size = match properties.iter().position(|property| property.name == "size") {
+		Some(idx) => {
+			let property = properties.remove(idx);
+			match property.value.parse() {
+				Ok(height) if height >= 0 => Size::new(0, height),
+				_                         => size(&property)?,
+			}
+		},
+		None      => return Err(ParseError::MissingProperty{section: origin.to_string(), property: "size".to_string()}),
+	};
+
+	let mut true_type = font::true_type(origin.clone(), path, tile_size);
+	for property in properties {
+		true_type = match &property.name[..] {
+			"size-reference" => true_type.size_reference(codepoint(&property)?),
+			"mode"           => true_type.mode(value(&property)?),
+			"codepage"       => true_type.codepage(property.value),
+			"align"          => true_type.align(value(&property)?),
+			"spacing"        => true_type.spacing(size(&property)?),
+			_                => return Err(unknown_property(&origin.to_string(), property)),
+		};
+	}
+	Ok(true_type)
+}
+
+/// Take the font's file out of its properties.
+fn font_path(origin: &Origin, properties: &mut Vec<Property>) -> Result<String, ParseError> {
+	match properties.iter().position(|property| property.name.is_empty()) {
+		Some(idx) => Ok(properties.remove(idx).value),
+		None      => Err(ParseError::MissingProperty{section: origin.to_string(), property: "file".to_string()}),
+	}
+}
+
+
+/// Parse a configuration string consisting only of the specified section, returning all its properties.
+fn single_section(config: &str, section: &str) -> Result<Vec<Property>, ParseError> {
+	let mut properties = vec![];
+	for statement in statements(config)? {
+		if statement.section != section {
+			return Err(ParseError::UnknownSection(statement.section));
+		}
+		properties.extend(statement.properties);
+	}
+	Ok(properties)
+}
+
+/// Parse a configuration string consisting of a single font section.
+fn font_section(config: &str) -> Result<(Origin, Vec<Property>), ParseError> {
+	let mut statements = statements(config)?.into_iter();
+	match (statements.next(), statements.next()) {
+		(Some(statement), None) => match statement.section.parse() {
+			Ok(origin) => Ok((origin, statement.properties)),
+			Err(_)     => Err(ParseError::UnknownSection(statement.section)),
+		},
+		(Some(_), Some(statement)) => Err(ParseError::Malformed(statement.section)),
+		(None, _)                  => Err(ParseError::Malformed(config.to_string())),
+	}
+}
+
+fn statements(config: &str) -> Result<Vec<Statement>, ParseError> {
+	let mut statements = vec![];
+	for statement in split(config, ';')?.into_iter().map(str::trim).filter(|statement| !statement.is_empty()) {
+		let colon = split(statement, ':')?;
+		let equals = split(statement, '=')?;
+
+		if colon.len() > 1 && colon[0].len() < equals[0].len() {
+			let body = &statement[colon[0].len() + 1..];
+			let mut properties = vec![];
+			for property in split(body, ',')?.into_iter().map(str::trim).filter(|property| !property.is_empty()) {
+				let parts = split(property, '=')?;
+				properties.push(if parts.len() == 1 {
+					Property{
+						name: "".to_string(),
+						value: unquote(property),
+					}
+				} else {
+					Property{
+						name: parts[0].trim().to_string(),
+						value: unquote(property[parts[0].len() + 1..].trim()),
+					}
+				});
+			}
+
+			statements.push(Statement{
+				section: colon[0].trim().to_string(),
+				properties: properties,
+			});
+		} else if equals.len() > 1 {
+			let mut key = equals[0].trim().splitn(2, '.');
+			match (key.next(), key.next()) {
+				(Some(section), Some(name)) => statements.push(Statement{
+					section: section.trim().to_string(),
+					properties: vec![Property{
+						name: name.trim().to_string(),
+						value: unquote(statement[equals[0].len() + 1..].trim()),
+					}],
+				}),
+				_                           => return Err(ParseError::Malformed(statement.to_string())),
+			}
+		} else {
+			return Err(ParseError::Malformed(statement.to_string()));
+		}
+	}
+	Ok(statements)
+}
+
+/// Split on the separator, except within `'`-quoted strings and `[]`-delimited lists.
+fn split(value: &str, separator: char) -> Result<Vec<&str>, ParseError> {
+	let mut parts = vec![];
+	let mut start = 0;
+	let mut quoted = false;
+	let mut depth = 0;
+	for (idx, c) in value.char_indices() {
+		match c {
+			'\''                                         => quoted = !quoted,
+			'[' if !quoted                               => depth += 1,
+			']' if !quoted && depth > 0                  => depth -= 1,
+			c if c == separator && !quoted && depth == 0 => {
+				parts.push(&value[start..idx]);
+				start = idx + c.len_utf8();
+			},
+			_                                            => (),
+		}
+	}
+
+	if quoted {
+		return Err(ParseError::Malformed(value.trim().to_string()));
+	}
+	parts.push(&value[start..]);
+	Ok(parts)
+}
+
+/// Remove the quotes around a value and unescape the `''`s within, see [`escape_config_string()`](fn.escape_config_string.html).
+fn unquote(value: &str) -> String {
+	match value.strip_prefix('\'').and_then(|value| value.strip_suffix('\'')) {
+		Some(value) => value.replace("''", "'"),
+		None        => value.to_string(),
+	}
+}
+
+fn value<T: FromStr>(property: &Property) -> Result<T, ParseError> {
+	property.value.parse().map_err(|_| invalid_value(property))
+}
+
+fn size(property: &Property) -> Result<Size, ParseError> {
+	let mut dims = property.value.splitn(2, 'x').map(|dim| dim.trim().parse::<i32>());
+	match (dims.next(), dims.next()) {
+		(Some(Ok(width)), Some(Ok(height))) if width >= 0 && height >= 0 => Ok(Size::new(width, height)),
+		_                                                                 => Err(invalid_value(property)),
+	}
+}
+
+/// A character, specified either as-is or by its code, like `0x5F` or `U+005F`.
+fn codepoint(property: &Property) -> Result<char, ParseError> {
+	let value = &property.value;
+	let code = match ["0x", "0X", "U+", "u+"].iter().filter_map(|prefix| value.strip_prefix(prefix)).next() {
+		Some(hex) => u32::from_str_radix(hex, 16).ok(),
+		None      => {
+			let mut chars = value.chars();
+			match (chars.next(), chars.next()) {
+				(Some(c), None) => Some(c as u32),
+				_               => None,
+			}
+		},
+	};
+	code.and_then(char::from_u32).ok_or_else(|| invalid_value(property))
+}
+
+fn unknown_property(section: &str, property: Property) -> ParseError {
+	ParseError::UnknownProperty{
+		section: section.to_string(),
+		property: if property.name.is_empty() {property.value} else {property.name},
+	}
+}
+
+fn invalid_value(property: &Property) -> ParseError {
+	ParseError::InvalidValue{
+		property: property.name.clone(),
+		value: property.value.clone(),
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use std::borrow::Cow;
+	use super::{parse, parse_input_filter, ParseError, Section};
+	use geometry::Size;
+	use terminal::config::{Cellsize, ConfigPart, Input, InputFilter, InputFilterEvent, InputFilterGroup, Log, LogLevel, LogMode, Output, Terminal, Window};
+	use terminal::config::font::{self, Align, Bitmap, Origin, RasterizationMode, ResizeFilter, ResizeMode, TrueType};
+
+
+	fn sections() -> Vec<Section> {
+		vec![Section::Terminal(Terminal::new("windows-1250".to_string())),
+		     Section::Window(Window::empty().size(Size::new(80, 25)).cellsize(Cellsize::Auto).title("It's; a [game], really".to_string())
+		                                    .icon("icon.ico").resizeable(true).fullscreen(false)),
+		     Section::Window(Window::empty().cellsize(Cellsize::Sized(Size::new(8, 16)))),
+		     Section::Input(Input::empty().precise_mouse(true).mouse_cursor(false).cursor_symbol('_').cursor_blink_rate(500)),
+		     Section::InputFilter(vec![InputFilter::Group{group: InputFilterGroup::Keyboard, both: false},
+		                               InputFilter::Event{name: InputFilterEvent::Escape, both: true},
+		                               InputFilter::Alnum{keys: "wasd".to_string(), both: false}]),
+		     Section::Output(Output::clean().postformatting(false).vsync(true)),
+		     Section::Log(Log::empty().file("log, 1.txt".to_string()).level(LogLevel::Trace).mode(LogMode::Append)),
+		     Section::Bitmap(font::bitmap(Origin::Offset('\u{E000}'), "tiles.png").size(Size::new(16, 16)).resize(Size::new(32, 32))
+		                                                                          .resize_filter(ResizeFilter::Bicubic).resize_mode(ResizeMode::Fit)
+		                                                                          .raw_size(Size::new(256, 256)).codepage("437".to_string())
+		                                                                          .align(Align::TopLeft).spacing(Size::new(2, 1))),
+		     Section::Bitmap(font::bitmap(Origin::Root, "cp437.png")),
+		     Section::TrueType(font::true_type(Origin::Named(Cow::Borrowed("huge")), "Mono.ttf", Size::new(0, 24)).size_reference('@')
+		                                                                                          .mode(RasterizationMode::Lcd).codepage("utf8".to_string())
+		                                                                                          .align(Align::Center).spacing(Size::new(2, 2))),
+		     Section::TrueType(font::true_type(Origin::Offset('\u{F000}'), "icons.OTF", Size::new(10, 20)))]
+	}
+
+
+	#[test]
+	fn round_trip_sections() {
+		for section in sections() {
+			assert_eq!(parse(&section.to_config_str()), Ok(vec![section.clone()]), "{}", section.to_config_str());
+		}
+	}
+
+	#[test]
+	fn round_trip_whole_string() {
+		let config = sections().iter().map(ConfigPart::to_config_str).collect::<String>();
+		assert_eq!(parse(&config), Ok(sections()));
+	}
+
+	#[test]
+	fn round_trip_from_str() {
+		for section in sections() {
+			let config = section.to_config_str();
+			match section {
+				Section::Terminal(section) => assert_eq!(config.parse(), Ok(section)),
+				Section::Window(section)   => assert_eq!(config.parse(), Ok(section)),
+				Section::Input(section)    => assert_eq!(config.parse(), Ok(section)),
+				Section::Output(section)   => assert_eq!(config.parse(), Ok(section)),
+				Section::Log(section)      => assert_eq!(config.parse(), Ok(section)),
+				Section::Bitmap(section)   => assert_eq!(config.parse::<Bitmap>(), Ok(section)),
+				Section::TrueType(section) => assert_eq!(config.parse::<TrueType>(), Ok(section)),
+				Section::InputFilter(ref filter) => assert_eq!(parse_input_filter(&config).as_ref(), Ok(filter)),
+			}
+		}
+	}
+
+	#[test]
+	fn forms() {
+		assert_eq!(parse("window.size = 10x5; window.title='a;b'"),
+		           Ok(vec![Section::Window(Window::empty().size(Size::new(10, 5))), Section::Window(Window::empty().title("a;b".to_string()))]));
+		assert_eq!(parse(" ;; "), Ok(vec![]));
+		assert_eq!(parse("font: x.TTF, size=12"), Ok(vec![Section::TrueType(font::true_type(Origin::Root, "x.TTF", Size::new(0, 12)))]));
+		assert_eq!(parse("input.filter=keyboard"), Ok(vec![Section::InputFilter(vec![InputFilter::Group{group: InputFilterGroup::Keyboard, both: false}])]));
+	}
+
+	#[test]
+	fn errors() {
+		let missing = |section: &str, property: &str| ParseError::MissingProperty{section: section.to_string(), property: property.to_string()};
+		assert_eq!(parse("window.title='oops"), Err(ParseError::Malformed("window.title='oops".to_string())));
+		assert_eq!(parse("window"), Err(ParseError::Malformed("window".to_string())));
+		assert_eq!(parse("screen.size=1x1"), Err(ParseError::UnknownSection("screen".to_string())));
+		assert_eq!(parse("window.colour=red"), Err(ParseError::UnknownProperty{section: "window".to_string(), property: "colour".to_string()}));
+		assert_eq!(parse("window.resizeable=maybe"), Err(ParseError::InvalidValue{property: "resizeable".to_string(), value: "maybe".to_string()}));
+		assert_eq!(parse("0xE000: size=8x8"), Err(missing("0xE000", "file")));
+		assert_eq!(parse("font: x.ttf"), Err(missing("[font]", "size")));
+		assert_eq!(parse("terminal: "), Err(missing("terminal", "encoding")));
+		assert_eq!(parse_input_filter("keyboard, no-op"), Err(ParseError::InvalidValue{property: "filter".to_string(), value: "no-op".to_string()}));
+		assert_eq!("input.filter=keyboard".parse::<Window>(), Err(ParseError::UnknownSection("input".to_string())));
+		assert_eq!("font: a.png; font: b.png".parse::<Bitmap>(), Err(ParseError::Malformed("font".to_string())));
+	}
+}
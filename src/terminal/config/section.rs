@@ -1,4 +1,5 @@
 use std::fmt;
+use std::str::FromStr;
 use std::path::Path;
 use geometry::Size;
 use terminal::config::{ConfigPart, escape_config_string};
@@ -182,6 +183,11 @@ impl Input {
 	///
 	/// Default: `500`.
 	pub fn cursor_blink_rate(mut self, cursor_blink_rate: i32) -> Self {self.cursor_blink_rate = Some(cursor_blink_rate); self}
+
+	/// Get the precise-mouse override, if any, for tracking whether it's on.
+	pub(crate) fn precise_mouse_value(&self) -> Option<bool> {
+		self.precise_mouse
+	}
 }
 
 impl Output {
@@ -366,6 +372,23 @@ impl fmt::Display for LogLevel {
 	}
 }
 
+impl FromStr for LogLevel {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<LogLevel, String> {
+		match s {
+			"none"    => Ok(LogLevel::None),
+			"fatal"   => Ok(LogLevel::Fatal),
+			"error"   => Ok(LogLevel::Error),
+			"warning" => Ok(LogLevel::Warning),
+			"info"    => Ok(LogLevel::Info),
+			"debug"   => Ok(LogLevel::Debug),
+			"trace"   => Ok(LogLevel::Trace),
+			_         => Err(format!("unknown log level \"{}\"", s)),
+		}
+	}
+}
+
 impl fmt::Display for LogMode {
 	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
 		formatter.write_str(match self {
@@ -374,3 +397,15 @@ impl fmt::Display for LogMode {
 		})
 	}
 }
+
+impl FromStr for LogMode {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<LogMode, String> {
+		match s {
+			"truncate" => Ok(LogMode::Truncate),
+			"append"   => Ok(LogMode::Append),
+			_          => Err(format!("unknown log mode \"{}\"", s)),
+		}
+	}
+}
@@ -0,0 +1,43 @@
+use std::fmt;
+use std::error;
+use std::result;
+
+
+/// Shorthand for results of fallible [`terminal`](index.html) operations.
+pub type Result<T> = result::Result<T, Error>;
+
+
+/// Things that can go wrong when talking to the terminal.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Error {
+	/// The backend failed to open the terminal, e.g. because there's no graphical environment.
+	OpenFailed,
+	/// A terminal is already open, and there can be only one.
+	AlreadyOpen,
+	/// The operation requires an open terminal.
+	NotOpen,
+	/// The terminal rejected a configuration string, which is included.
+	InvalidConfig(String),
+	/// The terminal yielded a code that isn't a valid Unicode scalar value.
+	InvalidCodepoint(i32),
+	/// A layer index outside of `0..=255`.
+	InvalidLayer(i32),
+	/// No layer of that name was registered with the [`Layers`](struct.Layers.html).
+	UnknownLayer(String),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			Error::OpenFailed                => write!(formatter, "failed to open the terminal"),
+			Error::AlreadyOpen               => write!(formatter, "the terminal is already open"),
+			Error::NotOpen                   => write!(formatter, "the terminal is not open"),
+			Error::InvalidConfig(ref config) => write!(formatter, "invalid configuration string \"{}\"", config),
+			Error::InvalidCodepoint(code)    => write!(formatter, "invalid codepoint {:#X}", code),
+			Error::InvalidLayer(index)       => write!(formatter, "layer {} outside of 0..=255", index),
+			Error::UnknownLayer(ref name)    => write!(formatter, "unknown layer \"{}\"", name),
+		}
+	}
+}
+
+impl error::Error for Error {}
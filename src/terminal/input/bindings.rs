@@ -0,0 +1,377 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+use terminal::{Event, KeyCode, Modifiers, MouseButton};
+
+
+/// What triggers a [`Chord`](struct.Chord.html), apart from its modifiers.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Trigger {
+	/// A keyboard key pressed, mouse buttons being [`Mouse`](#variant.Mouse) instead.
+	Key(KeyCode),
+	/// A mouse button pressed.
+	Mouse(MouseButton),
+	/// The mouse wheel scrolled "up"/"forwards"/"away".
+	ScrollUp,
+	/// The mouse wheel scrolled "down"/"backwards".
+	ScrollDown,
+}
+
+/// A key, mouse button or scroll direction along with the modifier keys held, e.g. `Ctrl+Shift+F5`.
+///
+/// Only Shift, Control and Alt count as modifiers, mouse buttons held being ignored.
+///
+/// Formats and parses as the modifiers followed by the trigger, joined with `+`s; parsing ignores case.
+///
+/// # Examples
+///
+/// ```
+/// use bear_lib_terminal::terminal::{Event, KeyCode, Modifiers};
+/// use bear_lib_terminal::terminal::input::{Chord, Trigger};
+///
+/// let chord = Chord::new(Trigger::Key(KeyCode::F5), Modifiers::CONTROL | Modifiers::SHIFT);
+/// assert_eq!(chord.to_string(), "Ctrl+Shift+F5");
+/// assert_eq!("shift+ctrl+f5".parse(), Ok(chord));
+///
+/// let event = Event::KeyPressed{key: KeyCode::F5, modifiers: Modifiers::CONTROL | Modifiers::SHIFT | Modifiers::MOUSE_LEFT, text: None};
+/// assert_eq!(Chord::from_event(&event), Some(chord));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Chord {
+	trigger: Trigger,
+	modifiers: Modifiers,
+}
+
+impl Chord {
+	/// Construct a chord triggered with the specified modifiers held.
+	///
+	/// [`KeyCode`](../enum.KeyCode.html)s of mouse buttons are turned into [`Trigger::Mouse`](enum.Trigger.html#variant.Mouse)s,
+	/// since that's what mouse events match.
+	pub fn new(trigger: Trigger, modifiers: Modifiers) -> Chord {
+		let trigger = match trigger {
+			Trigger::Key(key) => MouseButton::try_from(key).map(Trigger::Mouse).unwrap_or(trigger),
+			trigger           => trigger,
+		};
+
+		Chord{
+			trigger: trigger,
+			modifiers: modifiers & (Modifiers::SHIFT | Modifiers::CONTROL | Modifiers::ALT),
+		}
+	}
+
+	/// Get the chord a key press, mouse button press or scroll makes, if any.
+	pub fn from_event(event: &Event) -> Option<Chord> {
+		match *event {
+			Event::KeyPressed{key, modifiers, ..}             => Some(Chord::new(Trigger::Key(key), modifiers)),
+			Event::MouseDown{button, modifiers, ..}           => Some(Chord::new(Trigger::Mouse(button), modifiers)),
+			Event::MouseScroll{delta, modifiers} if delta < 0 => Some(Chord::new(Trigger::ScrollUp, modifiers)),
+			Event::MouseScroll{delta, modifiers} if delta > 0 => Some(Chord::new(Trigger::ScrollDown, modifiers)),
+			_                                                 => None,
+		}
+	}
+
+	/// Get the key, mouse button or scroll direction, never a [`Trigger::Key`](enum.Trigger.html#variant.Key) of a mouse button.
+	pub fn trigger(&self) -> Trigger {
+		self.trigger
+	}
+
+	/// Get the modifiers held, only ever containing Shift, Control and Alt.
+	pub fn modifiers(&self) -> Modifiers {
+		self.modifiers
+	}
+}
+
+impl From<Trigger> for Chord {
+	fn from(trigger: Trigger) -> Chord {
+		Chord::new(trigger, Modifiers::empty())
+	}
+}
+
+impl From<KeyCode> for Chord {
+	fn from(key: KeyCode) -> Chord {
+		Chord::from(Trigger::Key(key))
+	}
+}
+
+impl From<MouseButton> for Chord {
+	fn from(button: MouseButton) -> Chord {
+		Chord::from(Trigger::Mouse(button))
+	}
+}
+
+impl fmt::Display for Chord {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		for &(name, modifier) in MODIFIER_NAMES.iter() {
+			if self.modifiers.contains(modifier) {
+				write!(formatter, "{}+", name)?;
+			}
+		}
+
+		match self.trigger {
+			Trigger::Key(key)      => write!(formatter, "{}", key),
+			Trigger::Mouse(button) => write!(formatter, "{}", KeyCode::from(button)),
+			Trigger::ScrollUp      => formatter.write_str("ScrollUp"),
+			Trigger::ScrollDown    => formatter.write_str("ScrollDown"),
+		}
+	}
+}
+
+impl FromStr for Chord {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Chord, String> {
+		let mut parts = s.split('+').map(str::trim).collect::<Vec<_>>();
+		let trigger = match parts.pop() {
+			Some(name) if name.eq_ignore_ascii_case("ScrollUp")   => Trigger::ScrollUp,
+			Some(name) if name.eq_ignore_ascii_case("ScrollDown") => Trigger::ScrollDown,
+			Some(name)                                            => Trigger::Key(name.parse().map_err(|err| format!("{} in chord \"{}\"", err, s))?),
+			None                                                  => return Err(format!("empty chord \"{}\"", s)),
+		};
+
+		let mut modifiers = Modifiers::empty();
+		for part in parts {
+			match MODIFIER_NAMES.iter().find(|&&(name, _)| name.eq_ignore_ascii_case(part)) {
+				Some(&(_, modifier))                         => modifiers.insert(modifier),
+				None if part.eq_ignore_ascii_case("Control") => modifiers.insert(Modifiers::CONTROL),
+				None                                         => return Err(format!("unknown modifier \"{}\" in chord \"{}\"", part, s)),
+			}
+		}
+
+		Ok(Chord::new(trigger, modifiers))
+	}
+}
+
+
+/// A mapping of [`Chord`](struct.Chord.html)s to user-defined actions.
+///
+/// Every chord triggers at most one action, while an action can be triggered by any number of chords.
+///
+/// Bindings can be saved to and loaded from a text format with one action per line, its name followed by `=`
+/// and a comma-separated list of chords; blank lines and lines starting with `#` are ignored.
+/// Actions are written and read with their `Display` and `FromStr` implementations.
+///
+/// # Examples
+///
+/// ```
+/// use bear_lib_terminal::terminal::{Event, KeyCode, Modifiers};
+/// use bear_lib_terminal::terminal::input::{Bindings, Chord, Trigger};
+///
+/// let mut bindings: Bindings<String> = "# Movement
+///                                       west = Left, H, Num4
+///                                       quit = Escape, Ctrl+Q".parse().unwrap();
+///
+/// let event = Event::KeyPressed{key: KeyCode::Q, modifiers: Modifiers::CONTROL, text: None};
+/// assert_eq!(bindings.action_for(&event).map(String::as_str), Some("quit"));
+///
+/// // Ctrl+Q's taken, so it has to be unbound first
+/// let save = Chord::new(Trigger::Key(KeyCode::Q), Modifiers::CONTROL);
+/// assert_eq!(bindings.bind(save, "save".to_string()), Err(&"quit".to_string()));
+/// assert_eq!(bindings.rebind(save, "save".to_string()), Some("quit".to_string()));
+/// assert_eq!(bindings.to_string(), "west = Left, H, Num4\nquit = Escape\nsave = Ctrl+Q\n");
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Bindings<A> {
+	bindings: Vec<(Chord, A)>,
+}
+
+impl<A> Bindings<A> {
+	/// Construct an empty mapping.
+	pub fn new() -> Bindings<A> {
+		Bindings{
+			bindings: Vec::new(),
+		}
+	}
+
+	/// Get the action bound to the chord, if any.
+	pub fn action(&self, chord: &Chord) -> Option<&A> {
+		self.bindings.iter().find(|&&(bound, _)| bound == *chord).map(|(_, action)| action)
+	}
+
+	/// Get the action bound to the chord the event makes, if any, as per [`Chord::from_event()`](struct.Chord.html#method.from_event).
+	pub fn action_for(&self, event: &Event) -> Option<&A> {
+		Chord::from_event(event).and_then(|chord| self.action(&chord))
+	}
+
+	/// Bind the chord to the action, replacing and returning the action it was previously bound to, if any.
+	pub fn rebind(&mut self, chord: Chord, action: A) -> Option<A> {
+		let previous = self.unbind(&chord);
+		self.bindings.push((chord, action));
+		previous
+	}
+
+	/// Unbind the chord, returning the action it was bound to, if any.
+	pub fn unbind(&mut self, chord: &Chord) -> Option<A> {
+		self.bindings.iter().position(|&(bound, _)| bound == *chord).map(|idx| self.bindings.remove(idx).1)
+	}
+
+	/// Iterate over the chords and the actions they're bound to, in the order they were bound.
+	pub fn iter(&self) -> impl Iterator<Item = (Chord, &A)> {
+		self.bindings.iter().map(|(chord, action)| (*chord, action))
+	}
+}
+
+impl<A: PartialEq> Bindings<A> {
+	/// Bind the chord to the action.
+	///
+	/// Fails with the action the chord's already bound to, if it's a different one;
+	/// use [`rebind()`](#method.rebind) to replace it.
+	pub fn bind(&mut self, chord: Chord, action: A) -> Result<(), &A> {
+		match self.bindings.iter().position(|&(bound, _)| bound == chord) {
+			Some(idx) if self.bindings[idx].1 != action => Err(&self.bindings[idx].1),
+			Some(_)                                     => Ok(()),
+			None                                        => {
+				self.bindings.push((chord, action));
+				Ok(())
+			},
+		}
+	}
+
+	/// Iterate over the chords bound to the action, in the order they were bound.
+	pub fn chords<'a>(&'a self, action: &'a A) -> impl Iterator<Item = Chord> + 'a {
+		self.bindings.iter().filter(move |&(_, bound)| bound == action).map(|&(chord, _)| chord)
+	}
+
+	/// Unbind all chords bound to the action.
+	pub fn unbind_action(&mut self, action: &A) {
+		self.bindings.retain(|(_, bound)| bound != action);
+	}
+}
+
+impl<A> Default for Bindings<A> {
+	fn default() -> Bindings<A> {
+		Bindings::new()
+	}
+}
+
+/// Write the bindings in the text format, one line per action, in the order the actions were first bound.
+impl<A: PartialEq + fmt::Display> fmt::Display for Bindings<A> {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		for (idx, (_, action)) in self.bindings.iter().enumerate() {
+			if self.bindings[..idx].iter().any(|(_, previous)| previous == action) {
+				continue;
+			}
+
+			let chords = self.chords(action).map(|chord| chord.to_string()).collect::<Vec<_>>();
+			writeln!(formatter, "{} = {}", action, chords.join(", "))?;
+		}
+		Ok(())
+	}
+}
+
+/// Read bindings in the text format, failing on malformed lines, actions without chords and chords bound to more than one action.
+impl<A: Clone + PartialEq + FromStr> FromStr for Bindings<A> {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Bindings<A>, String> {
+		let mut bindings = Bindings::new();
+		for (idx, line) in s.lines().enumerate().map(|(idx, line)| (idx + 1, line.trim())) {
+			if line.is_empty() || line.starts_with('#') {
+				continue;
+			}
+
+			let mut parts = line.splitn(2, '=');
+			let (name, chords) = match (parts.next(), parts.next()) {
+				(Some(name), Some(chords)) => (name.trim(), chords),
+				_                          => return Err(format!("line {}: expected \"action = chords\", got \"{}\"", idx, line)),
+			};
+
+			let action: A = name.parse().map_err(|_| format!("line {}: invalid action \"{}\"", idx, name))?;
+			let chords = chords.split(',').map(str::trim).filter(|chord| !chord.is_empty())
+			                   .map(|chord| chord.parse::<Chord>().map_err(|err| format!("line {}: {}", idx, err)))
+			                   .collect::<Result<Vec<_>, _>>()?;
+			if chords.is_empty() {
+				return Err(format!("line {}: no chords for \"{}\"", idx, name));
+			}
+
+			for chord in chords {
+				if bindings.bind(chord, action.clone()).is_err() {
+					return Err(format!("line {}: {} is already bound to another action", idx, chord));
+				}
+			}
+		}
+		Ok(bindings)
+	}
+}
+
+
+static MODIFIER_NAMES: &[(&str, Modifiers)] = &[
+	("Ctrl",  Modifiers::CONTROL),
+	("Alt",   Modifiers::ALT),
+	("Shift", Modifiers::SHIFT),
+];
+
+
+#[cfg(test)]
+mod tests {
+	use super::{Bindings, Chord, Trigger};
+	use terminal::{KeyCode, Modifiers, MouseButton};
+
+
+	fn parse(s: &str) -> Result<Bindings<String>, String> {
+		s.parse()
+	}
+
+
+	#[test]
+	fn chord_round_trip() {
+		for chord in &["Ctrl+Alt+Shift+Delete", "Shift+ScrollUp", "ScrollDown", "Alt+MouseFifth", "Ctrl+Num4", "F12", "1"] {
+			assert_eq!(chord.parse::<Chord>().map(|chord| chord.to_string()), Ok(chord.to_string()));
+		}
+	}
+
+	#[test]
+	fn chord_parsing() {
+		assert_eq!(" control + A ".parse(), Ok(Chord::new(Trigger::Key(KeyCode::A), Modifiers::CONTROL)));
+		assert_eq!("Shift+Shift+a".parse(), Ok(Chord::new(Trigger::Key(KeyCode::A), Modifiers::SHIFT)));
+		assert_eq!("scrollup".parse(), Ok(Chord::from(Trigger::ScrollUp)));
+		assert_eq!("MouseMiddle".parse(), Ok(Chord::from(MouseButton::Middle)));
+		assert!("".parse::<Chord>().is_err());
+		assert!("Ctrl+".parse::<Chord>().is_err());
+		assert!("A+Ctrl".parse::<Chord>().is_err());
+		assert!("Meta+A".parse::<Chord>().is_err());
+	}
+
+	#[test]
+	fn chord_ignores_other_modifiers() {
+		let chord = Chord::new(Trigger::Key(KeyCode::MouseRight), Modifiers::ALT | Modifiers::MOUSE_LEFT);
+		assert_eq!(chord.trigger(), Trigger::Mouse(MouseButton::Right));
+		assert_eq!(chord.modifiers(), Modifiers::ALT);
+	}
+
+	#[test]
+	fn bindings_format() {
+		let bindings = parse("\n  # Comment\n\nquit=Escape,,Ctrl+Q,\n  west = Left  \n").unwrap();
+		assert_eq!(bindings.action(&Chord::new(Trigger::Key(KeyCode::Q), Modifiers::CONTROL)), Some(&"quit".to_string()));
+		assert_eq!(bindings.to_string(), "quit = Escape, Ctrl+Q\nwest = Left\n");
+		assert_eq!(parse(&bindings.to_string()), Ok(bindings));
+		assert_eq!(parse(""), Ok(Bindings::new()));
+	}
+
+	#[test]
+	fn bindings_merge_repeated_actions() {
+		let bindings = parse("quit = Escape\nwest = Left\nquit = Ctrl+Q, Escape").unwrap();
+		assert_eq!(bindings.chords(&"quit".to_string()).count(), 2);
+		assert_eq!(bindings.to_string(), "quit = Escape, Ctrl+Q\nwest = Left\n");
+	}
+
+	#[test]
+	fn bindings_errors() {
+		assert_eq!(parse("quit = Escape\nmenu"), Err("line 2: expected \"action = chords\", got \"menu\"".to_string()));
+		assert_eq!(parse("quit = Escape\n\nmenu = "), Err("line 3: no chords for \"menu\"".to_string()));
+		assert_eq!(parse("quit = Escape\nmenu = Escape"), Err("line 2: Escape is already bound to another action".to_string()));
+		assert_eq!(parse("quit = Hyper+Q"), Err("line 1: unknown modifier \"Hyper\" in chord \"Hyper+Q\"".to_string()));
+		assert_eq!("quit = Escape".parse::<Bindings<u8>>(), Err("line 1: invalid action \"quit\"".to_string()));
+	}
+
+	#[test]
+	fn rebinding() {
+		let mut bindings = Bindings::new();
+		assert_eq!(bindings.bind(Chord::from(KeyCode::Escape), 1), Ok(()));
+		assert_eq!(bindings.bind(Chord::from(KeyCode::Escape), 1), Ok(()));
+		assert_eq!(bindings.bind(Chord::from(KeyCode::Q), 1), Ok(()));
+		assert_eq!(bindings.rebind(Chord::from(KeyCode::Q), 2), Some(1));
+		assert_eq!(bindings.unbind(&Chord::from(KeyCode::Q)), Some(2));
+		bindings.unbind_action(&1);
+		assert_eq!(bindings, Bindings::new());
+	}
+}
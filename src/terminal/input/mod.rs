@@ -0,0 +1,570 @@
+//! Keyboard and mouse input: events, key codes and mapping them to actions with [`Bindings`](struct.Bindings.html).
+
+
+mod bindings;
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::{BitAnd, BitOr, Sub};
+use std::str::FromStr;
+use geometry::Point;
+use terminal;
+
+pub use self::bindings::{Bindings, Chord, Trigger};
+
+
+/// All pressable keys.
+///
+/// Keys BLT reports but this enum has no variant for come through as [`Unknown`](#variant.Unknown), so no event is ever lost.
+///
+/// # Examples
+///
+/// ```
+/// use bear_lib_terminal::terminal::{self, state, Event, KeyCode, Modifiers};
+/// use bear_lib_terminal::terminal::backend::Headless;
+///
+/// let backend = Headless::new();
+/// terminal::open_with(backend.clone(), "KeyCode example", 10, 1).unwrap();
+///
+/// let media_key = Event::KeyPressed{key: KeyCode::Unknown(0x68), modifiers: Modifiers::empty(), text: None};
+/// backend.push_event(media_key);
+/// assert_eq!(terminal::wait_event(), Some(media_key));
+/// assert_eq!(KeyCode::Unknown(0x04), KeyCode::A);
+/// assert_eq!(KeyCode::Unknown(0x04).to_string(), "A");
+/// assert_eq!(KeyCode::Unknown(0x68).to_string(), "0x68");
+///
+/// backend.push_event(Event::ShiftPressed);
+/// assert_eq!(terminal::wait_event(), Some(Event::ShiftPressed));
+/// assert!(state::key_pressed(KeyCode::Shift));
+/// assert!(!state::key_pressed(KeyCode::Alt));
+/// terminal::close();
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub enum KeyCode {
+	A,
+	B,
+	C,
+	D,
+	E,
+	F,
+	G,
+	H,
+	I,
+	J,
+	K,
+	L,
+	M,
+	N,
+	O,
+	P,
+	Q,
+	R,
+	S,
+	T,
+	U,
+	V,
+	W,
+	X,
+	Y,
+	Z,
+	/// Top-row `1/!` key.
+	Row1,
+	/// Top-row `2/@` key.
+	Row2,
+	/// Top-row `3/#` key.
+	Row3,
+	/// Top-row `4/$` key.
+	Row4,
+	/// Top-row `5/%` key.
+	Row5,
+	/// Top-row `6/^` key.
+	Row6,
+	/// Top-row `7/&` key.
+	Row7,
+	/// Top-row `8/*` key.
+	Row8,
+	/// Top-row `9/(` key.
+	Row9,
+	/// Top-row `0/)` key.
+	Row0,
+	/// Top-row &#96;/~ key.
+	Grave,
+	/// Top-row `-/_` key.
+	Minus,
+	/// Top-row `=/+` key.
+	Equals,
+	/// Second-row `[/{` key.
+	LeftBracket,
+	/// Second-row `]/}` key.
+	RightBracket,
+	/// Second-row `\/|` key.
+	Backslash,
+	/// Third-row `;/:` key.
+	Semicolon,
+	/// Third-row `'/"` key.
+	Apostrophe,
+	/// Fourth-row `,/<` key.
+	Comma,
+	/// Fourth-row `./>` key.
+	Period,
+	/// Fourth-row `//?` key.
+	Slash,
+	F1,
+	F2,
+	F3,
+	F4,
+	F5,
+	F6,
+	F7,
+	F8,
+	F9,
+	F10,
+	F11,
+	F12,
+	Enter,
+	Escape,
+	Backspace,
+	Tab,
+	Space,
+	Pause,
+	Insert,
+	Home,
+	PageUp,
+	Delete,
+	End,
+	PageDown,
+	/// Right arrow key.
+	Right,
+	/// Left arrow key.
+	Left,
+	/// Down arrow key.
+	Down,
+	/// Up arrow key.
+	Up,
+	/// Numpad `/` key.
+	NumDivide,
+	/// Numpad `*` key.
+	NumMultiply,
+	/// Numpad `-` key.
+	NumMinus,
+	/// Numpad `+` key.
+	NumPlus,
+	/// Numpad &#9166; key.
+	NumEnter,
+	/// Numpad `Del/.` key (output locale-dependent).
+	NumPeriod,
+	/// Numpad `1/End` key.
+	Num1,
+	/// Numpad 2/&#8595; key.
+	Num2,
+	/// Numpad `3/PageDown` key.
+	Num3,
+	/// Numpad 4/&#8592; key.
+	Num4,
+	/// Numpad `5` key.
+	Num5,
+	/// Numpad 6/&#8594; key.
+	Num6,
+	/// Numpad `7/Home` key.
+	Num7,
+	/// Numpad 8/&#8593; key.
+	Num8,
+	/// Numpad `9/PageUp` key.
+	Num9,
+	/// Numpad `0/Insert` key.
+	Num0,
+	/// Either Shift key.
+	///
+	/// Pressing or releasing it yields [`Event::ShiftPressed`](enum.Event.html#variant.ShiftPressed)
+	/// and [`Event::ShiftReleased`](enum.Event.html#variant.ShiftReleased), this is for checking it with
+	/// [`state::key_pressed()`](state/fn.key_pressed.html).
+	Shift,
+	/// Either Control key, see [`Shift`](#variant.Shift).
+	Control,
+	/// Either Alt key, see [`Shift`](#variant.Shift).
+	Alt,
+	/// Left mouse button.
+	MouseLeft,
+	/// Right mouse button.
+	MouseRight,
+	/// Middle mouse button a.k.a. pressed scroll wheel.
+	MouseMiddle,
+	MouseFourth,
+	MouseFifth,
+	/// A key without a variant of its own, e.g. F13 and up or one added in a later BLT version, with its raw BLT code.
+	///
+	/// Events never hold a code that has a variant, and keys compare by their codes, so `Unknown` with a named key's code
+	/// equals that key regardless of how it was constructed.
+	Unknown(i32),
+}
+
+impl KeyCode {
+	/// Get the variant with the same code, turning an [`Unknown`](#variant.Unknown) holding a named key's code into that key.
+	fn normalized(self) -> KeyCode {
+		terminal::to_keycode(terminal::from_keycode(self))
+	}
+}
+
+/// Keys compare by their BLT codes, see [`Unknown`](#variant.Unknown).
+impl PartialEq for KeyCode {
+	fn eq(&self, other: &KeyCode) -> bool {
+		terminal::from_keycode(*self) == terminal::from_keycode(*other)
+	}
+}
+
+impl Eq for KeyCode {}
+
+impl Hash for KeyCode {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		terminal::from_keycode(*self).hash(state);
+	}
+}
+
+/// Human-readable key names, e.g. `F5`, `PageUp` or `1` for the top-row `1`.
+///
+/// Keys without a variant of their own are named by their BLT code, e.g. `0x68`.
+impl fmt::Display for KeyCode {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		let code = terminal::from_keycode(*self);
+		match terminal::KEYCODES.iter().find(|&&(_, known, _)| known == code) {
+			Some(&(_, _, name)) => formatter.write_str(name),
+			None                => write!(formatter, "0x{:X}", code),
+		}
+	}
+}
+
+/// Parse a key name as produced by the `Display` implementation, ignoring case.
+impl FromStr for KeyCode {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<KeyCode, String> {
+		if let Some(&(key, _, _)) = terminal::KEYCODES.iter().find(|&&(_, _, name)| name.eq_ignore_ascii_case(s)) {
+			return Ok(key);
+		}
+
+		match s.get(..2) {
+			Some(prefix) if prefix.eq_ignore_ascii_case("0x") => i32::from_str_radix(&s[2..], 16).map(terminal::to_keycode)
+			                                                                                    .map_err(|_| format!("invalid key code \"{}\"", s)),
+			_                                                 => Err(format!("unknown key \"{}\"", s)),
+		}
+	}
+}
+
+
+/// A single input event.
+///
+/// # Examples
+///
+/// Text entry should use the typed characters rather than the keys, which depend on the keyboard layout:
+///
+/// ```
+/// use bear_lib_terminal::terminal::{self, Event, KeyCode, Modifiers};
+/// use bear_lib_terminal::terminal::backend::Headless;
+///
+/// let backend = Headless::new();
+/// terminal::open_with(backend.clone(), "Text entry example", 10, 1).unwrap();
+/// // "éa" typed on a French layout, then Backspace
+/// backend.push_event(Event::KeyPressed{key: KeyCode::Row2, modifiers: Modifiers::empty(), text: Some('é')});
+/// backend.push_event(Event::KeyPressed{key: KeyCode::Q, modifiers: Modifiers::empty(), text: Some('a')});
+/// backend.push_event(Event::KeyPressed{key: KeyCode::Backspace, modifiers: Modifiers::empty(), text: None});
+///
+/// assert_eq!(terminal::wait_event(), Some(Event::KeyPressed{key: KeyCode::Row2, modifiers: Modifiers::empty(), text: Some('é')}));
+/// assert_eq!(terminal::state::char(), Ok('é'));
+/// let mut entered = "é".to_string();
+/// while let Some(Event::KeyPressed{key, text, ..}) = terminal::wait_event() {
+///     match text {
+///         Some(c) => entered.push(c),
+///         None    => if key == KeyCode::Backspace {entered.pop();},
+///     }
+/// }
+/// assert_eq!(entered, "é");
+/// terminal::close();
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Event {
+	/// Terminal window closed.
+	Close,
+	/// Terminal window resized. Needs to have `window.resizeable = true` to occur.
+	///
+	/// Note, that, as of [`40e6253`](https://bitbucket.org/cfyzium/bearlibterminal/commits/40e625311f0cccc43b94633add4dec0d6b77c2b7),
+	/// the terminal window is cleared when resized.
+	Resize{
+		/// Width the terminal was resized to.
+		width: i32,
+		/// Heigth the terminal was resized to.
+		height: i32,
+	},
+	/// Mouse moved.
+	///
+	/// If [`precise-mouse`](config/struct.Input.html#method.precise_mouse) is off, generated each time mouse moves from cell to cell, otherwise,
+	/// when it moves from pixel to pixel.
+	MouseMove{
+		/// `0`-based cell index from the left to which the mouse cursor moved.
+		x: i32,
+		/// `0`-based cell index from the top to which the mouse cursor moved.
+		y: i32,
+		/// The modifier keys and mouse buttons held, e.g. while dragging.
+		modifiers: Modifiers,
+	},
+	/// Mouse moved from pixel to pixel, generated instead of [`MouseMove`](#variant.MouseMove)
+	/// if [`precise-mouse`](config/struct.Input.html#method.precise_mouse) was turned on with [`terminal::set()`](fn.set.html).
+	MouseMovePrecise{
+		/// The cell under the mouse cursor.
+		cell: Point,
+		/// The mouse cursor's position in pixels.
+		pixel: Point,
+		/// The modifier keys and mouse buttons held, e.g. while dragging.
+		modifiers: Modifiers,
+	},
+	/// A mouse button pressed.
+	MouseDown{
+		/// The button pressed.
+		button: MouseButton,
+		/// The cell under the mouse cursor.
+		cell: Point,
+		/// The mouse cursor's position in pixels.
+		pixel: Point,
+		/// Amount of fast consecutive clicks, e.g. `2` for a double-click.
+		clicks: i32,
+		/// The modifier keys and other mouse buttons held.
+		modifiers: Modifiers,
+	},
+	/// A mouse button released.
+	MouseUp{
+		/// The button released.
+		button: MouseButton,
+		/// The cell under the mouse cursor.
+		cell: Point,
+		/// The mouse cursor's position in pixels.
+		pixel: Point,
+		/// Amount of fast consecutive clicks the release ends.
+		clicks: i32,
+		/// The modifier keys and other mouse buttons held.
+		modifiers: Modifiers,
+	},
+	/// Mouse wheel moved.
+	MouseScroll{
+		/// Amount of steps the wheel rotated.
+		///
+		/// Positive when scrolled "down"/"backwards".
+		///
+		/// Negative when scrolled "up"/"forwards"/"away".
+		delta: i32,
+		/// The modifier keys and mouse buttons held.
+		modifiers: Modifiers,
+	},
+	/// A keyboard button pressed (might repeat, if set in OS).
+	///
+	/// Mouse buttons generate [`MouseDown`](#variant.MouseDown) instead.
+	KeyPressed{
+		/// The key pressed.
+		key: KeyCode,
+		/// The modifier keys and mouse buttons held.
+		modifiers: Modifiers,
+		/// The character typed, as per the current keyboard layout, if any.
+		///
+		/// Use this rather than the key for text entry, as it accounts for Shift, dead keys and non-US layouts.
+		text: Option<char>,
+	},
+	/// A keyboard button released.
+	///
+	/// Mouse buttons generate [`MouseUp`](#variant.MouseUp) instead.
+	KeyReleased{
+		/// The key released.
+		key: KeyCode,
+		/// The modifier keys and mouse buttons held.
+		modifiers: Modifiers,
+	},
+	/// The Shift key pressed (might repeat, if set in OS).
+	ShiftPressed,
+	/// The Shift key released.
+	ShiftReleased,
+	/// The Shift key pressed (might repeat, if set in OS).
+	ControlPressed,
+	/// The Control key released.
+	ControlReleased,
+	/// The Alt key pressed (might repeat, if set in OS).
+	AltPressed,
+	/// The Alt key released.
+	AltReleased,
+}
+
+/// A mouse button, as reported by [`Event::MouseDown`](enum.Event.html#variant.MouseDown) and
+/// [`Event::MouseUp`](enum.Event.html#variant.MouseUp).
+///
+/// Converts into the matching [`KeyCode`](enum.KeyCode.html), e.g. for [`state::key_pressed()`](state/fn.key_pressed.html).
+///
+/// # Examples
+///
+/// ```
+/// use bear_lib_terminal::terminal::{self, state, Event, KeyCode, Modifiers, MouseButton};
+/// use bear_lib_terminal::terminal::backend::Headless;
+/// use bear_lib_terminal::geometry::Point;
+///
+/// let backend = Headless::new();
+/// terminal::open_with(backend.clone(), "MouseButton example", 10, 5).unwrap();
+/// let double_click = Event::MouseDown{button: MouseButton::Left, cell: Point::new(2, 3), pixel: Point::new(20, 50),
+///                                     clicks: 2, modifiers: Modifiers::SHIFT};
+/// backend.push_event(double_click);
+/// assert_eq!(terminal::wait_event(), Some(double_click));
+/// assert!(state::key_pressed(MouseButton::Left.into()));
+/// assert_eq!(KeyCode::from(MouseButton::Right), KeyCode::MouseRight);
+/// terminal::close();
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MouseButton {
+	Left,
+	Right,
+	/// Middle mouse button a.k.a. pressed scroll wheel.
+	Middle,
+	Fourth,
+	Fifth,
+}
+
+impl From<MouseButton> for KeyCode {
+	fn from(button: MouseButton) -> KeyCode {
+		match button {
+			MouseButton::Left   => KeyCode::MouseLeft,
+			MouseButton::Right  => KeyCode::MouseRight,
+			MouseButton::Middle => KeyCode::MouseMiddle,
+			MouseButton::Fourth => KeyCode::MouseFourth,
+			MouseButton::Fifth  => KeyCode::MouseFifth,
+		}
+	}
+}
+
+
+/// Fails with the key itself if it isn't a mouse button.
+impl TryFrom<KeyCode> for MouseButton {
+	type Error = KeyCode;
+
+	fn try_from(key: KeyCode) -> Result<MouseButton, KeyCode> {
+		match key.normalized() {
+			KeyCode::MouseLeft   => Ok(MouseButton::Left),
+			KeyCode::MouseRight  => Ok(MouseButton::Right),
+			KeyCode::MouseMiddle => Ok(MouseButton::Middle),
+			KeyCode::MouseFourth => Ok(MouseButton::Fourth),
+			KeyCode::MouseFifth  => Ok(MouseButton::Fifth),
+			key                  => Err(key),
+		}
+	}
+}
+
+/// The button's own flag, e.g. [`MOUSE_LEFT`](struct.Modifiers.html#associatedconstant.MOUSE_LEFT) for the left one.
+impl From<MouseButton> for Modifiers {
+	fn from(button: MouseButton) -> Modifiers {
+		match button {
+			MouseButton::Left   => Modifiers::MOUSE_LEFT,
+			MouseButton::Right  => Modifiers::MOUSE_RIGHT,
+			MouseButton::Middle => Modifiers::MOUSE_MIDDLE,
+			MouseButton::Fourth => Modifiers::MOUSE_FOURTH,
+			MouseButton::Fifth  => Modifiers::MOUSE_FIFTH,
+		}
+	}
+}
+
+
+/// The modifier keys and mouse buttons held during an [`Event`](enum.Event.html), as a set of flags.
+///
+/// # Examples
+///
+/// ```
+/// use bear_lib_terminal::terminal::{self, Event, KeyCode, Modifiers};
+/// use bear_lib_terminal::terminal::backend::Headless;
+///
+/// let quit = Modifiers::ALT;
+/// assert!(quit.alt() && !quit.ctrl() && !quit.shift());
+/// assert!((Modifiers::SHIFT | Modifiers::MOUSE_LEFT).contains(Modifiers::MOUSE_LEFT));
+/// assert_eq!((Modifiers::SHIFT | Modifiers::CONTROL) - Modifiers::SHIFT, Modifiers::CONTROL);
+///
+/// let backend = Headless::new();
+/// terminal::open_with(backend.clone(), "Modifiers example", 10, 1).unwrap();
+/// backend.push_event(Event::KeyPressed{key: KeyCode::F4, modifiers: Modifiers::ALT, text: None});
+/// match terminal::wait_event() {
+///     Some(Event::KeyPressed{key: KeyCode::F4, modifiers, ..}) if modifiers.alt() => (),
+///     other                                                                        => panic!("expected Alt+F4, got {:?}", other),
+/// }
+/// terminal::close();
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Modifiers {
+	bits: u8,
+}
+
+impl Modifiers {
+	/// Either Shift key.
+	pub const SHIFT: Modifiers = Modifiers{bits: 1 << 0};
+	/// Either Control key.
+	pub const CONTROL: Modifiers = Modifiers{bits: 1 << 1};
+	/// Either Alt key.
+	pub const ALT: Modifiers = Modifiers{bits: 1 << 2};
+	/// The left mouse button.
+	pub const MOUSE_LEFT: Modifiers = Modifiers{bits: 1 << 3};
+	/// The right mouse button.
+	pub const MOUSE_RIGHT: Modifiers = Modifiers{bits: 1 << 4};
+	/// The middle mouse button.
+	pub const MOUSE_MIDDLE: Modifiers = Modifiers{bits: 1 << 5};
+	/// The fourth mouse button.
+	pub const MOUSE_FOURTH: Modifiers = Modifiers{bits: 1 << 6};
+	/// The fifth mouse button.
+	pub const MOUSE_FIFTH: Modifiers = Modifiers{bits: 1 << 7};
+
+	/// No modifiers held.
+	pub fn empty() -> Modifiers {
+		Modifiers::default()
+	}
+
+	/// Check whether no modifiers are held.
+	pub fn is_empty(&self) -> bool {
+		self.bits == 0
+	}
+
+	/// Check whether all of the other's modifiers are held.
+	pub fn contains(&self, other: Modifiers) -> bool {
+		self.bits & other.bits == other.bits
+	}
+
+	/// Add the other's modifiers.
+	pub fn insert(&mut self, other: Modifiers) {
+		self.bits |= other.bits;
+	}
+
+	/// Remove the other's modifiers.
+	pub fn remove(&mut self, other: Modifiers) {
+		self.bits &= !other.bits;
+	}
+
+	/// Check whether either Shift key is held.
+	pub fn shift(&self) -> bool {self.contains(Modifiers::SHIFT)}
+
+	/// Check whether either Control key is held.
+	pub fn ctrl (&self) -> bool {self.contains(Modifiers::CONTROL)}
+
+	/// Check whether either Alt key is held.
+	pub fn alt  (&self) -> bool {self.contains(Modifiers::ALT)}
+}
+
+impl BitOr for Modifiers {
+	type Output = Modifiers;
+
+	fn bitor(self, other: Modifiers) -> Modifiers {
+		Modifiers{bits: self.bits | other.bits}
+	}
+}
+
+impl BitAnd for Modifiers {
+	type Output = Modifiers;
+
+	fn bitand(self, other: Modifiers) -> Modifiers {
+		Modifiers{bits: self.bits & other.bits}
+	}
+}
+
+impl Sub for Modifiers {
+	type Output = Modifiers;
+
+	fn sub(self, other: Modifiers) -> Modifiers {
+		Modifiers{bits: self.bits & !other.bits}
+	}
+}
@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+use geometry::{Point, Rect};
+use terminal::{self, state, Error, Result};
+use terminal::scope::LayerGuard;
+
+
+/// A registry of named layers.
+///
+/// Layer indices are validated to be within BLT's `0..=255` on registration,
+/// and every operation on a layer by name leaves the current layer as it was.
+///
+/// # Examples
+///
+/// ```
+/// use bear_lib_terminal::terminal::{self, state, Error, Layers};
+/// use bear_lib_terminal::terminal::backend::Headless;
+///
+/// let mut layers = Layers::new();
+/// layers.register("map", 0).unwrap();
+/// layers.register("ui", 10).unwrap();
+/// assert_eq!(layers.register("tooltips", 256), Err(Error::InvalidLayer(256)));
+///
+/// let backend = Headless::new();
+/// terminal::open_with(backend.clone(), "layers example", 4, 1).unwrap();
+/// {
+///     let _ui = layers.select("ui").unwrap();
+///     terminal::print_xy(0, 0, "UI");
+/// }
+/// assert_eq!(state::layer(), 0);
+/// assert_eq!(backend.line(10, 0), "UI  ");
+/// terminal::close();
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Layers {
+	names: BTreeMap<String, i32>,
+}
+
+impl Layers {
+	/// Construct an empty registry.
+	pub fn new() -> Layers {
+		Layers::default()
+	}
+
+	/// Name the layer with the specified index, replacing any previous layer of that name.
+	///
+	/// Fails with [`Error::InvalidLayer`](enum.Error.html#variant.InvalidLayer) if the index isn't within `0..=255`.
+	pub fn register(&mut self, name: &str, index: i32) -> Result<()> {
+		if !(0..256).contains(&index) {
+			return Err(Error::InvalidLayer(index));
+		}
+
+		self.names.insert(name.to_string(), index);
+		Ok(())
+	}
+
+	/// Get the index of the named layer.
+	///
+	/// Fails with [`Error::UnknownLayer`](enum.Error.html#variant.UnknownLayer) if no such layer is registered,
+	/// as do all other methods taking a name.
+	pub fn index(&self, name: &str) -> Result<i32> {
+		self.names.get(name).cloned().ok_or_else(|| Error::UnknownLayer(name.to_string()))
+	}
+
+	/// Iterate over the registered names and indices, in alphabetical order.
+	pub fn iter(&self) -> impl Iterator<Item = (&str, i32)> {
+		self.names.iter().map(|(name, &index)| (&name[..], index))
+	}
+
+	/// Select the named layer until the returned guard is dropped.
+	pub fn select(&self, name: &str) -> Result<LayerGuard> {
+		self.index(name).map(LayerGuard::new)
+	}
+
+	/// Crop the named layer, see [`terminal::crop()`](fn.crop.html), or, if `None`, remove its crop.
+	pub fn crop(&self, name: &str, rect: Option<Rect>) -> Result<()> {
+		let _layer = self.select(name)?;
+		terminal::set_crop(rect);
+		Ok(())
+	}
+
+	/// Get the named layer's crop area, see [`state::crop()`](state/fn.crop.html).
+	pub fn crop_area(&self, name: &str) -> Result<Option<Rect>> {
+		let _layer = self.select(name)?;
+		Ok(state::crop())
+	}
+
+	/// Clear the whole named layer.
+	pub fn clear(&self, name: &str) -> Result<()> {
+		let index = self.index(name)?;
+		clear_layers(index, index);
+		Ok(())
+	}
+
+	/// Clear all layers with indices above the named one's, registered or not.
+	pub fn clear_above(&self, name: &str) -> Result<()> {
+		let index = self.index(name)?;
+		clear_layers(index + 1, 255);
+		Ok(())
+	}
+}
+
+
+/// Clear the layers within the specified range, both inclusive.
+fn clear_layers(from: i32, to: i32) {
+	let area = Rect::from_size(Point::new(0, 0), state::size());
+	let _layer = LayerGuard::new(state::layer());
+	for index in from..to + 1 {
+		terminal::layer(index);
+		terminal::clear(Some(area));
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::Layers;
+	use geometry::Rect;
+	use terminal::{self, state, Error};
+	use terminal::backend::Headless;
+
+
+	fn layers() -> Layers {
+		let mut layers = Layers::new();
+		layers.register("map", 0).unwrap();
+		layers.register("items", 1).unwrap();
+		layers.register("ui", 10).unwrap();
+		layers
+	}
+
+
+	#[test]
+	fn registration() {
+		let mut layers = layers();
+		assert_eq!(layers.register("below", -1), Err(Error::InvalidLayer(-1)));
+		assert_eq!(layers.register("above", 256), Err(Error::InvalidLayer(256)));
+		assert_eq!(layers.register("ui", 255), Ok(()));
+		assert_eq!(layers.index("ui"), Ok(255));
+		assert_eq!(layers.index("fog"), Err(Error::UnknownLayer("fog".to_string())));
+		assert_eq!(layers.iter().collect::<Vec<_>>(), vec![("items", 1), ("map", 0), ("ui", 255)]);
+	}
+
+	#[test]
+	fn crops_are_per_layer() {
+		let layers = layers();
+		terminal::open_with(Headless::new(), "crops_are_per_layer", 4, 1).unwrap();
+		layers.crop("ui", Some(Rect::from_values(0, 0, 2, 1))).unwrap();
+		layers.crop("items", Some(Rect::from_values(1, 0, 3, 1))).unwrap();
+		layers.crop("items", None).unwrap();
+		assert_eq!(layers.crop_area("ui"), Ok(Some(Rect::from_values(0, 0, 2, 1))));
+		assert_eq!(layers.crop_area("items"), Ok(None));
+		assert_eq!(layers.crop_area("map"), Ok(None));
+		assert_eq!(layers.crop("fog", None), Err(Error::UnknownLayer("fog".to_string())));
+		assert_eq!(state::layer(), 0);
+		terminal::close();
+	}
+
+	#[test]
+	fn clearing() {
+		let layers = layers();
+		let backend = Headless::new();
+		terminal::open_with(backend.clone(), "clearing", 4, 1).unwrap();
+		for &(layer, text) in &[("map", "...."), ("items", " !  "), ("ui", "UI  ")] {
+			let _layer = layers.select(layer).unwrap();
+			terminal::print_xy(0, 0, text);
+		}
+		terminal::layer(20);
+		terminal::print_xy(0, 0, "?");
+		terminal::layer(1);
+
+		layers.clear_above("items").unwrap();
+		assert_eq!((backend.line(0, 0), backend.line(1, 0), backend.line(10, 0), backend.line(20, 0)),
+		           ("....".to_string(), " !  ".to_string(), "    ".to_string(), "    ".to_string()));
+		layers.clear("map").unwrap();
+		assert_eq!((backend.line(0, 0), backend.line(1, 0)), ("    ".to_string(), " !  ".to_string()));
+		assert_eq!(state::layer(), 1);
+		terminal::close();
+	}
+}
@@ -1,18 +1,34 @@
 //! Rusticized interface for the FFI.
 
 
-mod input;
+pub mod input;
+mod error;
+mod session;
 pub mod config;
 pub mod state;
+pub mod backend;
+pub mod buffer;
+pub mod scope;
+mod put;
+mod layers;
 
 use std::char;
+use std::convert::TryFrom;
+use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
 use colors::Color;
-use geometry::{Rect, Point, Size};
-use self::config::{ConfigPart, Window};
+use geometry::{Alignment, Rect, Point, Size};
+use self::config::{ConfigPart, Section, Window};
+use self::backend::{Backend, Native};
 use bear_lib_terminal_sys as ffi;
 use bear_lib_terminal_sys::ColorT;
 
-pub use self::input::{Event, KeyCode};
+#[doc(inline)]
+pub use self::input::{Event, KeyCode, Modifiers, MouseButton};
+pub use self::error::{Error, Result};
+pub use self::session::Terminal;
+pub use self::put::{Corners, PutExt};
+pub use self::layers::Layers;
 
 
 /// Creates the terminal window of the specified size with the specified title, without showing it.
@@ -20,32 +36,56 @@ pub use self::input::{Event, KeyCode};
 ///
 /// Equivalent to the [`terminal_open()` C API function](http://foo.wyrd.name/en:bearlibterminal:reference#open) with a subsequent call to
 /// the [`terminal_set()` C API function](http://foo.wyrd.name/en:bearlibterminal:reference#set) with the title.
-pub fn open(title: &str, width: u32, height: u32) {
-	ffi::open();
-	set(Window::empty().size(Size::new(width as i32, height as i32)).title(title.to_string()));
+///
+/// Fails with [`Error::AlreadyOpen`](enum.Error.html#variant.AlreadyOpen) if a terminal is already open
+/// and with [`Error::OpenFailed`](enum.Error.html#variant.OpenFailed) if the window couldn't be created.
+///
+/// Kept for compatibility, prefer [`Terminal::open()`](struct.Terminal.html#method.open), which closes the terminal when it goes out of scope.
+pub fn open(title: &str, width: u32, height: u32) -> Result<()> {
+	open_with(Native, title, width, height)
+}
+
+/// Equivalent to [`open()`](fn.open.html), but all subsequent functions from the module will use the specified
+/// [`Backend`](backend/trait.Backend.html) until [`close()`](fn.close.html) is called.
+///
+/// Pass a [`backend::Headless`](backend/struct.Headless.html) to run without a window, e.g. in tests.
+pub fn open_with<B: Backend + 'static>(backend: B, title: &str, width: u32, height: u32) -> Result<()> {
+	start_session(backend, title, width, height).map(|_| ())
 }
 
 /// Closes the terminal window, causing all subsequent functions from the module (apart from [`open()`](fn.open.html)) to fail
 ///
 /// Equivalent to the [`terminal_close()` C API function](http://foo.wyrd.name/en:bearlibterminal:reference#close).
+///
+/// Afterwards the [`Native`](backend/struct.Native.html) backend is used again.
 pub fn close() {
-	ffi::close();
+	with_backend(|backend| backend.close());
+	BACKEND.with(|current| *current.borrow_mut() = Box::new(Native));
+	SESSION.with(|session| session.set(None));
+	CROPS.with(|crops| crops.borrow_mut().clear());
+	PRECISE_MOUSE.with(|precise| precise.set(false));
 }
 
 /// Invoke the [`terminal_set()` C API function](http://foo.wyrd.name/en:bearlibterminal:reference#set) with the argument's `config_str`.
 ///
-/// Returns `false` iff the config string is malformed.
+/// Fails with [`Error::InvalidConfig`](enum.Error.html#variant.InvalidConfig) iff the config string is malformed.
 ///
 /// For build-in [`ConfigPart`](config/trait.ConfigPart.html)s see the [`config`](config/index.html) module.
-pub fn set<T: ConfigPart>(cfg: T) -> bool {
-	ffi::set(&*&cfg.to_config_str())
+pub fn set<T: ConfigPart>(cfg: T) -> Result<()> {
+	let config = cfg.to_config_str();
+	if with_backend(|backend| backend.set(&config)) {
+		track_precise_mouse(&config);
+		Ok(())
+	} else {
+		Err(Error::InvalidConfig(config))
+	}
 }
 
 /// Flushes all changes made to the screen; also shows the window after the [`open()`](fn.open.html) call
 ///
 /// Equivalent to the [`terminal_refresh()` C API function](http://foo.wyrd.name/en:bearlibterminal:reference#refresh).
 pub fn refresh() {
-	ffi::refresh();
+	with_backend(|backend| backend.refresh());
 }
 
 /// Clears the screen (either partailly or fully)
@@ -57,54 +97,56 @@ pub fn refresh() {
 /// [`terminal_clear_area()` C API function](http://foo.wyrd.name/en:bearlibterminal:reference#clear_area).
 pub fn clear(area: Option<Rect>) {
 	match area {
-		Some(rect) => ffi::clear_area(rect.top_left.x, rect.top_left.y, rect.size.width, rect.size.height),
-		None       => ffi::clear(),
+		Some(rect) => with_backend(|backend| backend.clear_area(rect.top_left.x, rect.top_left.y, rect.size.width, rect.size.height)),
+		None       => with_backend(|backend| backend.clear()),
 	}
 }
 
 /// Sets the current layer's crop area.
 ///
+/// The crop area can be read back with [`state::crop()`](state/fn.crop.html).
+///
 /// <sub>I don't get it either, refer the [`terminal_crop()` C API function's documentation](http://foo.wyrd.name/en:bearlibterminal:reference#crop).</sub>
 pub fn crop(rect: Rect) {
-	ffi::crop(rect.top_left.x, rect.top_left.y, rect.size.width, rect.size.height);
+	set_crop(Some(rect));
 }
 
 /// Selects the current layer.
 ///
-/// The layer `index` must be between 0 and 255.
+/// The layer `index` must be between 0 and 255, see [`Layers`](struct.Layers.html) for a registry of validated, named layers.
 /// For more information consult the documentation for the [`terminal_layer()` C API function](http://foo.wyrd.name/en:bearlibterminal:reference#layer).
 pub fn layer(index: i32) {
-	ffi::layer(index);
+	with_backend(|backend| backend.layer(index));
 }
 
 /// Sets the current foreground color, which will affect all the output functions called later.
 ///
 /// This is equivalent to the [`terminal_color()` C API function](http://foo.wyrd.name/en:bearlibterminal:reference#color).
 pub fn set_foreground(color: Color) {
-	ffi::color(to_color_t(color));
+	with_backend(|backend| backend.color(color));
 }
 
-/// Sets the foreground color before calling the function and resets it afterwards.
-pub fn with_foreground<F: FnOnce()>(color: Color, callback: F) {
-	let current = ffi::state_color(ffi::TK_COLOR);
-	set_foreground(color);
-	callback();
-	ffi::color(current);
+/// Sets the foreground color before calling the function and resets it afterwards, returning the function's result.
+///
+/// For a non-closure equivalent see [`scope::ColorGuard`](scope/struct.ColorGuard.html).
+pub fn with_foreground<R, F: FnOnce() -> R>(color: Color, callback: F) -> R {
+	let _guard = scope::ColorGuard::foreground(color);
+	callback()
 }
 
 /// Sets the current background color, which will affect all the output functions called later.
 ///
 /// This is equivalent to the [`terminal_bkcolor()` C API function](http://foo.wyrd.name/en:bearlibterminal:reference#bkcolor).
 pub fn set_background(color: Color) {
-	ffi::bkcolor(to_color_t(color));
+	with_backend(|backend| backend.bkcolor(color));
 }
 
-/// Sets the background color before calling the function and resets it afterwards.
-pub fn with_background<F: FnOnce()>(color: Color, callback: F) {
-	let current = ffi::state_color(ffi::TK_BKCOLOR);
-	set_background(color);
-	callback();
-	ffi::bkcolor(current);
+/// Sets the background color before calling the function and resets it afterwards, returning the function's result.
+///
+/// For a non-closure equivalent see [`scope::ColorGuard`](scope/struct.ColorGuard.html).
+pub fn with_background<R, F: FnOnce() -> R>(color: Color, callback: F) -> R {
+	let _guard = scope::ColorGuard::background(color);
+	callback()
 }
 
 /// Sets the current foreground and background color, which will affect all the output functions called later.
@@ -115,13 +157,12 @@ pub fn set_colors(fg: Color, bg: Color) {
 	set_background(bg);
 }
 
-/// Sets the foreground and background color before calling the function and resets them afterwards.
-pub fn with_colors<F: FnOnce()>(fg: Color, bg: Color, callback: F) {
-	with_foreground(fg, ||
-		with_background(bg, ||
-			callback()
-		)
-	);
+/// Sets the foreground and background color before calling the function and resets them afterwards, returning the function's result.
+///
+/// For a non-closure equivalent see [`scope::ColorGuard`](scope/struct.ColorGuard.html).
+pub fn with_colors<R, F: FnOnce() -> R>(fg: Color, bg: Color, callback: F) -> R {
+	let _guard = scope::ColorGuard::colors(fg, bg);
+	callback()
 }
 
 /// Enable or disable composition, (dis)allowing for "stacking" tiles on top of each other in the same cell.
@@ -129,63 +170,114 @@ pub fn with_colors<F: FnOnce()>(fg: Color, bg: Color, callback: F) {
 /// For details and other uses consult the documentation for the
 /// [`terminal_composition()` C API function](http://foo.wyrd.name/en:bearlibterminal:reference#composition).
 pub fn composition(enable: bool) {
-	ffi::composition(enable);
+	with_backend(|backend| backend.composition(enable));
 }
 
 /// Prints the specified character to the specified location.
 ///
 /// Equivalent to the [`terminal_put()` C API function](http://foo.wyrd.name/en:bearlibterminal:reference#put).
 pub fn put(point: Point, cell: char) {
-	ffi::put(point.x, point.y, cell as i32);
+	with_backend(|backend| backend.put(point.x, point.y, cell as i32));
 }
 
 /// Equivalent to [`put()`](fn.put.html) with a `Point` constructed from the first two arguments.
 pub fn put_xy(x: i32, y: i32, cell: char) {
-	ffi::put(x, y, cell as i32);
+	with_backend(|backend| backend.put(x, y, cell as i32));
 }
 
 /// Prints the specified character to the specified pixel-offsetted location, gradient-colouring it from the corners.
 ///
+/// See [`PutExt`](struct.PutExt.html) for a builder with offset animation helpers.
+///
 /// For details see the docs for the [`terminal_put_ext()` C API function](http://foo.wyrd.name/en:bearlibterminal:reference#put_ext).
-pub fn put_ext(pos: Point, offset: Point, cell: char, corners: &Vec<Color>) {
-	ffi::put_ext(pos.x, pos.y, offset.x, offset.y, cell as i32, &corners.iter().cloned().map(to_color_t).collect::<Vec<_>>()[..]);
+pub fn put_ext(pos: Point, offset: Point, cell: char, corners: Corners) {
+	with_backend(|backend| backend.put_ext(pos.x, pos.y, offset.x, offset.y, cell as i32, corners.to_array()));
 }
 
 /// Get the character in the specified coordinates on the specified layer.
 ///
-/// Returns 0 if the cell is empty on the specified layer.
+/// Returns `'\0'` if the cell is empty on the specified layer.
+///
+/// Fails with [`Error::NotOpen`](enum.Error.html#variant.NotOpen) if no terminal is open,
+/// and with [`Error::InvalidCodepoint`](enum.Error.html#variant.InvalidCodepoint) if the cell doesn't contain a valid character.
 ///
 /// Consult the documentation for the [`terminal_pick()` C API function](http://foo.wyrd.name/en:bearlibterminal:reference#pick) for more data.
-pub fn pick(point: Point, index: i32) -> char {
-	char::from_u32(ffi::pick(point.x, point.y, index) as u32).unwrap()
+pub fn pick(point: Point, index: i32) -> Result<char> {
+	require_open()?;
+	to_char(with_backend(|backend| backend.pick(point.x, point.y, index)))
 }
 
 /// Get the color of the character in the specified coordinates on the specified layer.
 ///
+/// Fails with [`Error::NotOpen`](enum.Error.html#variant.NotOpen) if no terminal is open.
+///
 /// Consult the documentation for the [`terminal_pick_color()` C API function](http://foo.wyrd.name/en:bearlibterminal:reference#pick_color),
 /// despite its laconicity.
-pub fn pick_foreground_color(point: Point, index: i32) -> Color {
-	from_color_t(ffi::pick_color(point.x, point.y, index))
+pub fn pick_foreground_color(point: Point, index: i32) -> Result<Color> {
+	require_open()?;
+	Ok(with_backend(|backend| backend.pick_color(point.x, point.y, index)))
 }
 
 /// Get the background color in the specified coordinates.
 ///
+/// Fails with [`Error::NotOpen`](enum.Error.html#variant.NotOpen) if no terminal is open.
+///
 /// Consult the documentation for the [`terminal_pick_bkcolor()` C API function](http://foo.wyrd.name/en:bearlibterminal:reference#pick_bkcolor)
 /// for the same amount of information.
-pub fn pick_background_color(point: Point) -> Color {
-	from_color_t(ffi::pick_bkcolor(point.x, point.y))
+pub fn pick_background_color(point: Point) -> Result<Color> {
+	require_open()?;
+	Ok(with_backend(|backend| backend.pick_bkcolor(point.x, point.y)))
 }
 
 /// Prints the specified string to the specified location, formatting it along the way.
 ///
+/// Returns the dimensions of the printed text in cells.
+///
 /// For formatting spec see the docs for the [`terminal_print()` C API function](http://foo.wyrd.name/en:bearlibterminal:reference#print).
-pub fn print(point: Point, value: &str) {
-	let _ = ffi::print(point.x, point.y, value);
+pub fn print(point: Point, value: &str) -> Size {
+	print_ext(Rect::from_size(point, Size::new(0, 0)), Alignment::default(), value)
 }
 
 /// Equivalent to [`print()`](fn.print.html) with a `Point` constructed from the first two arguments.
-pub fn print_xy(x: i32, y: i32, value: &str) {
-	print(Point::new(x, y), value);
+pub fn print_xy(x: i32, y: i32, value: &str) -> Size {
+	print(Point::new(x, y), value)
+}
+
+/// Prints the specified string inside the specified box, wrapping and aligning it therein.
+///
+/// A `0` box width or height means no bound in that direction, the alignment is then relative to the box's top-left corner.
+///
+/// Returns the dimensions of the printed text in cells.
+///
+/// Equivalent to the [`terminal_print_ext()` C API function](http://foo.wyrd.name/en:bearlibterminal:reference#print).
+///
+/// # Examples
+///
+/// ```
+/// use bear_lib_terminal::terminal;
+/// use bear_lib_terminal::terminal::backend::Headless;
+/// use bear_lib_terminal::geometry::{Alignment, HorizontalAlignment, Rect, Size, VerticalAlignment};
+///
+/// let backend = Headless::new();
+/// terminal::open_with(backend.clone(), "print_ext() example", 12, 3).unwrap();
+/// let size = terminal::print_ext(Rect::from_values(0, 0, 12, 3),
+///                                Alignment::new(HorizontalAlignment::Center, VerticalAlignment::Middle),
+///                                "Hello there, adventurer");
+/// assert_eq!(size, Size::new(12, 2));
+/// assert_eq!(backend.line(0, 0), "Hello there,");
+/// assert_eq!(backend.line(0, 1), " adventurer ");
+///
+/// // Spaces are kept except where the text wraps, and an unbounded height aligns within the text's own
+/// terminal::clear(None);
+/// let size = terminal::print_ext(Rect::from_values(0, 0, 6, 0), Alignment::default(), "ab  cd  efgh");
+/// assert_eq!(size, Size::new(6, 2));
+/// terminal::print_ext(Rect::from_values(6, 0, 6, 0), Alignment::new(HorizontalAlignment::Right, VerticalAlignment::Bottom), "x");
+/// assert_eq!(backend.line(0, 0), "ab  cd     x");
+/// assert_eq!(backend.line(0, 1), "efgh        ");
+/// terminal::close();
+/// ```
+pub fn print_ext(rect: Rect, align: Alignment, value: &str) -> Size {
+	with_backend(|backend| backend.print_ext(rect.top_left.x, rect.top_left.y, rect.size.width, rect.size.height, align, value))
 }
 
 /// Calculate the argument's width/height without printing it.
@@ -194,22 +286,31 @@ pub fn print_xy(x: i32, y: i32, value: &str) {
 ///
 /// Refer to the [docs for the `terminal_measure()` C API function](http://foo.wyrd.name/en:bearlibterminal:reference#measure), note,
 /// that the return type therein is incorrect.
+#[deprecated(note = "returns either the width or the height, use measure_ext() instead")]
 pub fn measure(value: &str) -> i32 {
-	ffi::measure(value)
+	with_backend(|backend| backend.measure(value))
+}
+
+/// Calculate the dimensions the argument would have if it were printed with [`print_ext()`](fn.print_ext.html) inside a box of the specified size.
+///
+/// Equivalent to the [`terminal_measure_ext()` C API function](http://foo.wyrd.name/en:bearlibterminal:reference#measure).
+pub fn measure_ext(bbox: Size, value: &str) -> Size {
+	with_backend(|backend| backend.measure_ext(bbox.width, bbox.height, value))
 }
 
 /// Check, whether the next [`read_event()`](fn.read_event.html) call will return `Some`.
 ///
 /// Consult the [documentation for the `terminal_has_input()` C API function](http://foo.wyrd.name/en:bearlibterminal:reference#has_input).
 pub fn has_input() -> bool {
-	ffi::has_input()
+	with_backend(|backend| backend.has_input())
 }
 
 /// Returns the next event, blocks until one's available.
 ///
 /// This is equivalent to the [`terminal_read()` C API function](http://foo.wyrd.name/en:bearlibterminal:reference#read).
 pub fn wait_event() -> Option<Event> {
-	to_event(ffi::read())
+	let code = with_backend(|backend| backend.read());
+	to_event(code)
 }
 
 /// Returns an instance of [`EventIterator`](struct.EventIterator.html), an infinite iterator over Terminal events.
@@ -241,7 +342,7 @@ pub fn read_event() -> Option<Event> {
 ///
 /// This is equivalent to the [`terminal_peek()` C API function](http://foo.wyrd.name/en:bearlibterminal:reference#peek).
 pub fn peek_event() -> Option<Event> {
-	match ffi::peek() {
+	match with_backend(|backend| backend.peek()) {
 		0 => None,
 		event => to_event(event),
 	}
@@ -259,14 +360,14 @@ pub fn peek_event() -> Option<Event> {
 /// Refer to the [documentation for the for the `terminal_read_str()` C API function](http://foo.wyrd.name/en:bearlibterminal:reference#read_str)
 /// for specific information.
 pub fn read_str(point: Point, max: i32) -> Option<String> {
-	ffi::read_str(point.x, point.y, max)
+	with_backend(|backend| backend.read_str(point.x, point.y, max))
 }
 
 /// Sleep for the specified amount of milliseconds.
 ///
 /// See the [`terminal_delay()` C API function's documentation](http://foo.wyrd.name/en:bearlibterminal:reference#delay).
 pub fn delay(period: i32) {
-	ffi::delay(period)
+	with_backend(|backend| backend.delay(period))
 }
 
 
@@ -302,6 +403,95 @@ impl Iterator for EventIterator {
 }
 
 
+thread_local!(static BACKEND: RefCell<Box<dyn Backend>> = RefCell::new(Box::new(Native)));
+
+fn with_backend<R, F: FnOnce(&mut dyn Backend) -> R>(func: F) -> R {
+	BACKEND.with(|backend| func(&mut **backend.borrow_mut()))
+}
+
+// Identifier of the currently open terminal, if any, so a stale Terminal handle doesn't close a newer one.
+thread_local!(static SESSION: Cell<Option<u32>> = const { Cell::new(None) });
+thread_local!(static SESSION_COUNT: Cell<u32> = const { Cell::new(0) });
+
+// BearLibTerminal doesn't expose the crop areas, so they're tracked here, per layer.
+thread_local!(static CROPS: RefCell<HashMap<i32, Rect>> = RefCell::new(HashMap::new()));
+
+// Nor does it expose whether precise-mouse is on, which decides the kind of mouse-move events.
+thread_local!(static PRECISE_MOUSE: Cell<bool> = const { Cell::new(false) });
+
+/// Remember the last precise-mouse value the configuration string sets, if any.
+///
+/// Strings that don't [parse](config/fn.parse.html) leave it as it was.
+fn track_precise_mouse(config: &str) {
+	let sections = config::parse(config).unwrap_or_default();
+	let precise = sections.iter().rev().find_map(|section| match *section {
+		Section::Input(ref input) => input.precise_mouse_value(),
+		_                         => None,
+	});
+
+	if let Some(precise) = precise {
+		PRECISE_MOUSE.with(|current| current.set(precise));
+	}
+}
+
+/// Crop the current layer to the specified area or, if `None`, remove its crop, which BLT denotes with a zero-sized area.
+fn set_crop(area: Option<Rect>) {
+	let layer = state::layer();
+	let rect = match area {
+		Some(rect) => {
+			CROPS.with(|crops| crops.borrow_mut().insert(layer, rect));
+			rect
+		},
+		None       => {
+			CROPS.with(|crops| crops.borrow_mut().remove(&layer));
+			Rect::from_values(0, 0, 0, 0)
+		},
+	};
+	with_backend(|backend| backend.crop(rect.top_left.x, rect.top_left.y, rect.size.width, rect.size.height));
+}
+
+fn session() -> Option<u32> {
+	SESSION.with(|session| session.get())
+}
+
+fn require_open() -> Result<()> {
+	match session() {
+		Some(_) => Ok(()),
+		None    => Err(Error::NotOpen),
+	}
+}
+
+/// Switch to and open the backend, returning the new session's identifier.
+///
+/// Reverts to [`Native`](backend/struct.Native.html) if it fails to open.
+fn start_session<B: Backend + 'static>(backend: B, title: &str, width: u32, height: u32) -> Result<u32> {
+	if session().is_some() {
+		return Err(Error::AlreadyOpen);
+	}
+
+	BACKEND.with(|current| *current.borrow_mut() = Box::new(backend));
+	if !with_backend(|backend| backend.open()) {
+		BACKEND.with(|current| *current.borrow_mut() = Box::new(Native));
+		return Err(Error::OpenFailed);
+	}
+
+	let id = SESSION_COUNT.with(|count| {
+		count.set(count.get().wrapping_add(1));
+		count.get()
+	});
+	SESSION.with(|session| session.set(Some(id)));
+
+	if let Err(error) = set(Window::empty().size(Size::new(width as i32, height as i32)).title(title.to_string())) {
+		close();
+		return Err(error);
+	}
+	Ok(id)
+}
+
+fn to_char(code: i32) -> Result<char> {
+	char::from_u32(code as u32).ok_or(Error::InvalidCodepoint(code))
+}
+
 fn from_color_t(color: ColorT) -> Color {
 	let alpha = ((color >> 24) & 0xFF) as u8;
 	let red   = ((color >> 16) & 0xFF) as u8;
@@ -320,110 +510,144 @@ fn to_color_t(color: Color) -> ColorT {
 	)
 }
 
-fn to_keycode(code: i32) -> Option<KeyCode> {
-	match code {
-		ffi::TK_A            => Some(KeyCode::A),
-		ffi::TK_B            => Some(KeyCode::B),
-		ffi::TK_C            => Some(KeyCode::C),
-		ffi::TK_D            => Some(KeyCode::D),
-		ffi::TK_E            => Some(KeyCode::E),
-		ffi::TK_F            => Some(KeyCode::F),
-		ffi::TK_G            => Some(KeyCode::G),
-		ffi::TK_H            => Some(KeyCode::H),
-		ffi::TK_I            => Some(KeyCode::I),
-		ffi::TK_J            => Some(KeyCode::J),
-		ffi::TK_K            => Some(KeyCode::K),
-		ffi::TK_L            => Some(KeyCode::L),
-		ffi::TK_M            => Some(KeyCode::M),
-		ffi::TK_N            => Some(KeyCode::N),
-		ffi::TK_O            => Some(KeyCode::O),
-		ffi::TK_P            => Some(KeyCode::P),
-		ffi::TK_Q            => Some(KeyCode::Q),
-		ffi::TK_R            => Some(KeyCode::R),
-		ffi::TK_S            => Some(KeyCode::S),
-		ffi::TK_T            => Some(KeyCode::T),
-		ffi::TK_U            => Some(KeyCode::U),
-		ffi::TK_V            => Some(KeyCode::V),
-		ffi::TK_W            => Some(KeyCode::W),
-		ffi::TK_X            => Some(KeyCode::X),
-		ffi::TK_Y            => Some(KeyCode::Y),
-		ffi::TK_Z            => Some(KeyCode::Z),
-		ffi::TK_1            => Some(KeyCode::Row1),
-		ffi::TK_2            => Some(KeyCode::Row2),
-		ffi::TK_3            => Some(KeyCode::Row3),
-		ffi::TK_4            => Some(KeyCode::Row4),
-		ffi::TK_5            => Some(KeyCode::Row5),
-		ffi::TK_6            => Some(KeyCode::Row6),
-		ffi::TK_7            => Some(KeyCode::Row7),
-		ffi::TK_8            => Some(KeyCode::Row8),
-		ffi::TK_9            => Some(KeyCode::Row9),
-		ffi::TK_0            => Some(KeyCode::Row0),
-		ffi::TK_ENTER        => Some(KeyCode::Enter),
-		ffi::TK_ESCAPE       => Some(KeyCode::Escape),
-		ffi::TK_BACKSPACE    => Some(KeyCode::Backspace),
-		ffi::TK_TAB          => Some(KeyCode::Tab),
-		ffi::TK_SPACE        => Some(KeyCode::Space),
-		ffi::TK_MINUS        => Some(KeyCode::Minus),
-		ffi::TK_EQUALS       => Some(KeyCode::Equals),
-		ffi::TK_LBRACKET     => Some(KeyCode::LeftBracket),
-		ffi::TK_RBRACKET     => Some(KeyCode::RightBracket),
-		ffi::TK_BACKSLASH    => Some(KeyCode::Backslash),
-		ffi::TK_SEMICOLON    => Some(KeyCode::Semicolon),
-		ffi::TK_APOSTROPHE   => Some(KeyCode::Apostrophe),
-		ffi::TK_GRAVE        => Some(KeyCode::Grave),
-		ffi::TK_COMMA        => Some(KeyCode::Comma),
-		ffi::TK_PERIOD       => Some(KeyCode::Period),
-		ffi::TK_SLASH        => Some(KeyCode::Slash),
-		ffi::TK_F1           => Some(KeyCode::F1),
-		ffi::TK_F2           => Some(KeyCode::F2),
-		ffi::TK_F3           => Some(KeyCode::F3),
-		ffi::TK_F4           => Some(KeyCode::F4),
-		ffi::TK_F5           => Some(KeyCode::F5),
-		ffi::TK_F6           => Some(KeyCode::F6),
-		ffi::TK_F7           => Some(KeyCode::F7),
-		ffi::TK_F8           => Some(KeyCode::F8),
-		ffi::TK_F9           => Some(KeyCode::F9),
-		ffi::TK_F10          => Some(KeyCode::F10),
-		ffi::TK_F11          => Some(KeyCode::F11),
-		ffi::TK_F12          => Some(KeyCode::F12),
-		ffi::TK_PAUSE        => Some(KeyCode::Pause),
-		ffi::TK_INSERT       => Some(KeyCode::Insert),
-		ffi::TK_HOME         => Some(KeyCode::Home),
-		ffi::TK_PAGEUP       => Some(KeyCode::PageUp),
-		ffi::TK_DELETE       => Some(KeyCode::Delete),
-		ffi::TK_END          => Some(KeyCode::End),
-		ffi::TK_PAGEDOWN     => Some(KeyCode::PageDown),
-		ffi::TK_RIGHT        => Some(KeyCode::Right),
-		ffi::TK_LEFT         => Some(KeyCode::Left),
-		ffi::TK_DOWN         => Some(KeyCode::Down),
-		ffi::TK_UP           => Some(KeyCode::Up),
-		ffi::TK_KP_DIVIDE    => Some(KeyCode::NumDivide),
-		ffi::TK_KP_MULTIPLY  => Some(KeyCode::NumMultiply),
-		ffi::TK_KP_MINUS     => Some(KeyCode::NumMinus),
-		ffi::TK_KP_PLUS      => Some(KeyCode::NumPlus),
-		ffi::TK_KP_ENTER     => Some(KeyCode::NumEnter),
-		ffi::TK_KP_1         => Some(KeyCode::Num1),
-		ffi::TK_KP_2         => Some(KeyCode::Num2),
-		ffi::TK_KP_3         => Some(KeyCode::Num3),
-		ffi::TK_KP_4         => Some(KeyCode::Num4),
-		ffi::TK_KP_5         => Some(KeyCode::Num5),
-		ffi::TK_KP_6         => Some(KeyCode::Num6),
-		ffi::TK_KP_7         => Some(KeyCode::Num7),
-		ffi::TK_KP_8         => Some(KeyCode::Num8),
-		ffi::TK_KP_9         => Some(KeyCode::Num9),
-		ffi::TK_KP_0         => Some(KeyCode::Num0),
-		ffi::TK_KP_PERIOD    => Some(KeyCode::NumPeriod),
-		ffi::TK_MOUSE_LEFT   => Some(KeyCode::MouseLeft),
-		ffi::TK_MOUSE_RIGHT  => Some(KeyCode::MouseRight),
-		ffi::TK_MOUSE_MIDDLE => Some(KeyCode::MouseMiddle),
-		ffi::TK_MOUSE_X1     => Some(KeyCode::MouseFourth),
-		ffi::TK_MOUSE_X2     => Some(KeyCode::MouseFifth),
-		_                    => None,
-	}
-}
-
+/// The BLT key codes checked for each of the [`Modifiers`](struct.Modifiers.html).
+static MODIFIERS: &[(i32, Modifiers)] = &[
+	(ffi::TK_SHIFT,        Modifiers::SHIFT),
+	(ffi::TK_CONTROL,      Modifiers::CONTROL),
+	(ffi::TK_ALT,          Modifiers::ALT),
+	(ffi::TK_MOUSE_LEFT,   Modifiers::MOUSE_LEFT),
+	(ffi::TK_MOUSE_RIGHT,  Modifiers::MOUSE_RIGHT),
+	(ffi::TK_MOUSE_MIDDLE, Modifiers::MOUSE_MIDDLE),
+	(ffi::TK_MOUSE_X1,     Modifiers::MOUSE_FOURTH),
+	(ffi::TK_MOUSE_X2,     Modifiers::MOUSE_FIFTH),
+];
+
+/// Declare the mapping between [`KeyCode`](enum.KeyCode.html)s, BLT's key codes and human-readable key names as both the `KEYCODES` table
+/// and `from_keycode()`, an exhaustive `match`, so that a variant without a row doesn't compile.
+macro_rules! keycodes {
+	($($key:ident => $code:path, $name:expr,)*) => {
+		static KEYCODES: &[(KeyCode, i32, &str)] = &[$((KeyCode::$key, $code, $name),)*];
+
+		fn from_keycode(key: KeyCode) -> i32 {
+			match key {
+				$(KeyCode::$key => $code,)*
+				KeyCode::Unknown(code) => code,
+			}
+		}
+	};
+}
+
+keycodes! {
+	A            => ffi::TK_A,            "A",
+	B            => ffi::TK_B,            "B",
+	C            => ffi::TK_C,            "C",
+	D            => ffi::TK_D,            "D",
+	E            => ffi::TK_E,            "E",
+	F            => ffi::TK_F,            "F",
+	G            => ffi::TK_G,            "G",
+	H            => ffi::TK_H,            "H",
+	I            => ffi::TK_I,            "I",
+	J            => ffi::TK_J,            "J",
+	K            => ffi::TK_K,            "K",
+	L            => ffi::TK_L,            "L",
+	M            => ffi::TK_M,            "M",
+	N            => ffi::TK_N,            "N",
+	O            => ffi::TK_O,            "O",
+	P            => ffi::TK_P,            "P",
+	Q            => ffi::TK_Q,            "Q",
+	R            => ffi::TK_R,            "R",
+	S            => ffi::TK_S,            "S",
+	T            => ffi::TK_T,            "T",
+	U            => ffi::TK_U,            "U",
+	V            => ffi::TK_V,            "V",
+	W            => ffi::TK_W,            "W",
+	X            => ffi::TK_X,            "X",
+	Y            => ffi::TK_Y,            "Y",
+	Z            => ffi::TK_Z,            "Z",
+	Row1         => ffi::TK_1,            "1",
+	Row2         => ffi::TK_2,            "2",
+	Row3         => ffi::TK_3,            "3",
+	Row4         => ffi::TK_4,            "4",
+	Row5         => ffi::TK_5,            "5",
+	Row6         => ffi::TK_6,            "6",
+	Row7         => ffi::TK_7,            "7",
+	Row8         => ffi::TK_8,            "8",
+	Row9         => ffi::TK_9,            "9",
+	Row0         => ffi::TK_0,            "0",
+	Enter        => ffi::TK_ENTER,        "Enter",
+	Escape       => ffi::TK_ESCAPE,       "Escape",
+	Backspace    => ffi::TK_BACKSPACE,    "Backspace",
+	Tab          => ffi::TK_TAB,          "Tab",
+	Space        => ffi::TK_SPACE,        "Space",
+	Minus        => ffi::TK_MINUS,        "Minus",
+	Equals       => ffi::TK_EQUALS,       "Equals",
+	LeftBracket  => ffi::TK_LBRACKET,     "LeftBracket",
+	RightBracket => ffi::TK_RBRACKET,     "RightBracket",
+	Backslash    => ffi::TK_BACKSLASH,    "Backslash",
+	Semicolon    => ffi::TK_SEMICOLON,    "Semicolon",
+	Apostrophe   => ffi::TK_APOSTROPHE,   "Apostrophe",
+	Grave        => ffi::TK_GRAVE,        "Grave",
+	Comma        => ffi::TK_COMMA,        "Comma",
+	Period       => ffi::TK_PERIOD,       "Period",
+	Slash        => ffi::TK_SLASH,        "Slash",
+	F1           => ffi::TK_F1,           "F1",
+	F2           => ffi::TK_F2,           "F2",
+	F3           => ffi::TK_F3,           "F3",
+	F4           => ffi::TK_F4,           "F4",
+	F5           => ffi::TK_F5,           "F5",
+	F6           => ffi::TK_F6,           "F6",
+	F7           => ffi::TK_F7,           "F7",
+	F8           => ffi::TK_F8,           "F8",
+	F9           => ffi::TK_F9,           "F9",
+	F10          => ffi::TK_F10,          "F10",
+	F11          => ffi::TK_F11,          "F11",
+	F12          => ffi::TK_F12,          "F12",
+	Pause        => ffi::TK_PAUSE,        "Pause",
+	Insert       => ffi::TK_INSERT,       "Insert",
+	Home         => ffi::TK_HOME,         "Home",
+	PageUp       => ffi::TK_PAGEUP,       "PageUp",
+	Delete       => ffi::TK_DELETE,       "Delete",
+	End          => ffi::TK_END,          "End",
+	PageDown     => ffi::TK_PAGEDOWN,     "PageDown",
+	Right        => ffi::TK_RIGHT,        "Right",
+	Left         => ffi::TK_LEFT,         "Left",
+	Down         => ffi::TK_DOWN,         "Down",
+	Up           => ffi::TK_UP,           "Up",
+	NumDivide    => ffi::TK_KP_DIVIDE,    "NumDivide",
+	NumMultiply  => ffi::TK_KP_MULTIPLY,  "NumMultiply",
+	NumMinus     => ffi::TK_KP_MINUS,     "NumMinus",
+	NumPlus      => ffi::TK_KP_PLUS,      "NumPlus",
+	NumEnter     => ffi::TK_KP_ENTER,     "NumEnter",
+	Num1         => ffi::TK_KP_1,         "Num1",
+	Num2         => ffi::TK_KP_2,         "Num2",
+	Num3         => ffi::TK_KP_3,         "Num3",
+	Num4         => ffi::TK_KP_4,         "Num4",
+	Num5         => ffi::TK_KP_5,         "Num5",
+	Num6         => ffi::TK_KP_6,         "Num6",
+	Num7         => ffi::TK_KP_7,         "Num7",
+	Num8         => ffi::TK_KP_8,         "Num8",
+	Num9         => ffi::TK_KP_9,         "Num9",
+	Num0         => ffi::TK_KP_0,         "Num0",
+	NumPeriod    => ffi::TK_KP_PERIOD,    "NumPeriod",
+	Shift        => ffi::TK_SHIFT,        "Shift",
+	Control      => ffi::TK_CONTROL,      "Control",
+	Alt          => ffi::TK_ALT,          "Alt",
+	MouseLeft    => ffi::TK_MOUSE_LEFT,   "MouseLeft",
+	MouseRight   => ffi::TK_MOUSE_RIGHT,  "MouseRight",
+	MouseMiddle  => ffi::TK_MOUSE_MIDDLE, "MouseMiddle",
+	MouseFourth  => ffi::TK_MOUSE_X1,     "MouseFourth",
+	MouseFifth   => ffi::TK_MOUSE_X2,     "MouseFifth",
+}
+
+/// Convert a BLT key code to a `KeyCode`, which is [`Unknown`](enum.KeyCode.html#variant.Unknown) only if no variant has that code.
+fn to_keycode(code: i32) -> KeyCode {
+	KEYCODES.iter().find(|&&(_, known, _)| known == code).map(|&(key, _, _)| key).unwrap_or(KeyCode::Unknown(code))
+}
+
+/// Convert a BLT event code to an `Event`, `0` meaning there's none.
 fn to_event(code: i32) -> Option<Event> {
 	match code {
+		0                    => None,
 		ffi::TK_CLOSE        => Some(Event::Close),
 		ffi::TK_RESIZED      => Some(get_window_resize()),
 		ffi::TK_MOUSE_MOVE   => Some(get_mouse_move()),
@@ -440,50 +664,157 @@ fn to_key_event(code: i32) -> Option<Event> {
 		ffi::TK_SHIFT   => Some(if released {Event::ShiftReleased}   else {Event::ShiftPressed}),
 		ffi::TK_CONTROL => Some(if released {Event::ControlReleased} else {Event::ControlPressed}),
 		ffi::TK_ALT     => Some(if released {Event::AltReleased}     else {Event::AltPressed}),
-		key             => {
-			let ctrl  = ffi::check(ffi::TK_CONTROL);
-			let shift = ffi::check(ffi::TK_SHIFT);
-
-			match to_keycode(key) {
-				Some(converted) => Some(get_key(released, converted, ctrl, shift)),
-				None            => None,
-			}
-		}
+		key             => Some(match MouseButton::try_from(to_keycode(key)) {
+			Ok(button) => get_mouse_button(released, button),
+			Err(key)   => get_key(released, key, get_modifiers()),
+		}),
 	}
 }
 
 fn get_window_resize() -> Event {
 	Event::Resize{
-		width: ffi::state(ffi::TK_WIDTH),
-		height: ffi::state(ffi::TK_HEIGHT),
+		width: with_backend(|backend| backend.state(ffi::TK_WIDTH)),
+		height: with_backend(|backend| backend.state(ffi::TK_HEIGHT)),
 	}
 }
 
 fn get_mouse_move() -> Event {
-	Event::MouseMove{
-		x: ffi::state(ffi::TK_MOUSE_X),
-		y: ffi::state(ffi::TK_MOUSE_Y),
+	if PRECISE_MOUSE.with(Cell::get) {
+		Event::MouseMovePrecise{
+			cell: state::mouse::position(),
+			pixel: state::mouse::pixel_position(),
+			modifiers: get_modifiers(),
+		}
+	} else {
+		Event::MouseMove{
+			x: with_backend(|backend| backend.state(ffi::TK_MOUSE_X)),
+			y: with_backend(|backend| backend.state(ffi::TK_MOUSE_Y)),
+			modifiers: get_modifiers(),
+		}
+	}
+}
+
+fn get_mouse_button(released: bool, button: MouseButton) -> Event {
+	let cell      = state::mouse::position();
+	let pixel     = state::mouse::pixel_position();
+	let clicks    = state::mouse::clicks();
+	let modifiers = get_modifiers() - Modifiers::from(button);
+
+	if released {
+		Event::MouseUp{button: button, cell: cell, pixel: pixel, clicks: clicks, modifiers: modifiers}
+	} else {
+		Event::MouseDown{button: button, cell: cell, pixel: pixel, clicks: clicks, modifiers: modifiers}
 	}
 }
 
 fn get_mouse_scroll() -> Event {
 	Event::MouseScroll{
-		delta: ffi::state(ffi::TK_MOUSE_WHEEL),
+		delta: with_backend(|backend| backend.state(ffi::TK_MOUSE_WHEEL)),
+		modifiers: get_modifiers(),
+	}
+}
+
+fn get_modifiers() -> Modifiers {
+	let mut modifiers = Modifiers::empty();
+	for &(code, modifier) in MODIFIERS.iter() {
+		if with_backend(|backend| backend.check(code)) {
+			modifiers.insert(modifier);
+		}
+	}
+	modifiers
+}
+
+fn get_text() -> Option<char> {
+	match with_backend(|backend| backend.state(ffi::TK_WCHAR)) {
+		0    => None,
+		code => to_char(code).ok(),
 	}
 }
 
-fn get_key(released: bool, key: KeyCode, ctrl: bool, shift: bool) -> Event {
+fn get_key(released: bool, key: KeyCode, modifiers: Modifiers) -> Event {
 	if released {
 		Event::KeyReleased{
 			key: key,
-			ctrl: ctrl,
-			shift: shift,
+			modifiers: modifiers,
 		}
 	} else {
 		Event::KeyPressed{
 			key: key,
-			ctrl: ctrl,
-			shift: shift,
+			modifiers: modifiers,
+			text: get_text(),
+		}
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::{config, from_keycode, to_keycode, Error, Event, KeyCode, Modifiers, MouseButton, KEYCODES};
+	use geometry::Point;
+	use terminal::{self, state};
+	use terminal::backend::Headless;
+
+
+	#[test]
+	fn keycodes_round_trip() {
+		for &(key, code, name) in KEYCODES.iter() {
+			assert_eq!(from_keycode(key), code);
+			assert_eq!(to_keycode(code), key);
+			assert_eq!(name.parse(), Ok(key));
+			assert_eq!(key.to_string(), name);
 		}
+		assert_eq!(to_keycode(0x7F), KeyCode::Unknown(0x7F));
+		assert_eq!(from_keycode(KeyCode::Unknown(0x7F)), 0x7F);
+		assert_eq!(KeyCode::Unknown(from_keycode(KeyCode::Escape)), KeyCode::Escape);
+	}
+
+	#[test]
+	fn picking_requires_open() {
+		assert_eq!(terminal::pick(Point::new(0, 0), 0), Err(Error::NotOpen));
+		assert_eq!(terminal::pick_foreground_color(Point::new(0, 0), 0), Err(Error::NotOpen));
+		assert_eq!(terminal::pick_background_color(Point::new(0, 0)), Err(Error::NotOpen));
+	}
+
+	#[test]
+	fn mouse_buttons() {
+		let backend = Headless::new();
+		terminal::open_with(backend.clone(), "mouse_buttons", 10, 5).unwrap();
+		let press = Event::MouseDown{button: MouseButton::Left, cell: Point::new(2, 3), pixel: Point::new(20, 50), clicks: 2, modifiers: Modifiers::SHIFT};
+		let release = Event::MouseUp{button: MouseButton::Right, cell: Point::new(2, 3), pixel: Point::new(21, 50), clicks: 1, modifiers: Modifiers::MOUSE_LEFT};
+		backend.push_event(press);
+		backend.push_event(release);
+		assert_eq!(terminal::wait_event(), Some(press));
+		assert_eq!(state::mouse::clicks(), 2);
+		assert_eq!(terminal::wait_event(), Some(release));
+		assert!(state::key_pressed(KeyCode::MouseLeft));
+		assert!(!state::key_pressed(KeyCode::MouseRight));
+		terminal::close();
+	}
+
+	#[test]
+	fn precise_mouse_follows_config() {
+		let backend = Headless::new();
+		terminal::open_with(backend.clone(), "precise_mouse_follows_config", 10, 5).unwrap();
+		let moved = Event::MouseMove{x: 2, y: 3, modifiers: Modifiers::MOUSE_LEFT};
+		let dragged = Event::MouseMovePrecise{cell: Point::new(2, 3), pixel: Point::new(21, 50), modifiers: Modifiers::MOUSE_LEFT};
+
+		terminal::set(config::Window::empty().title("precise-mouse=true".to_string())).unwrap();
+		backend.push_event(moved);
+		assert_eq!(terminal::wait_event(), Some(moved));
+
+		terminal::set(config::Input::empty().precise_mouse(true)).unwrap();
+		backend.push_event(dragged);
+		assert_eq!(terminal::wait_event(), Some(dragged));
+
+		terminal::set(config::parse("input: precise-mouse = false").unwrap().remove(0)).unwrap();
+		backend.push_event(moved);
+		assert_eq!(terminal::wait_event(), Some(moved));
+
+		terminal::set(config::Input::empty().precise_mouse(true)).unwrap();
+		terminal::close();
+		terminal::open_with(backend.clone(), "precise_mouse_follows_config", 10, 5).unwrap();
+		backend.push_event(moved);
+		assert_eq!(terminal::wait_event(), Some(moved));
+		terminal::close();
 	}
 }
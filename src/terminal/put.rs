@@ -0,0 +1,153 @@
+//! Type-safe [`put_ext()`](../fn.put_ext.html) corner colours and a builder for animated, pixel-offset cells.
+
+
+use colors::Color;
+use geometry::{Point, Size};
+use terminal;
+
+
+/// The colours of a [`put_ext()`](fn.put_ext.html)ed cell's corners.
+///
+/// # Examples
+///
+/// ```
+/// # use bear_lib_terminal::Color;
+/// # use bear_lib_terminal::terminal::Corners;
+/// assert_eq!(Corners::from(Color::RED).to_array(), Some([Color::RED; 4]));
+/// assert_eq!(Corners::None.to_array(), None);
+/// assert_eq!(Corners::new(Color::RED, Color::GREEN, Color::BLUE, Color::WHITE),
+///            Corners::from([Color::RED, Color::GREEN, Color::BLUE, Color::WHITE]));
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Corners {
+	/// Use the current foreground colour, like passing a null corners pointer to the C API.
+	#[default]
+	None,
+	/// Colour all four corners the same.
+	Uniform(Color),
+	/// Colour each corner separately, in top-left, bottom-left, bottom-right, top-right order.
+	Each([Color; 4]),
+}
+
+impl Corners {
+	/// Construct corners from their colours, in the same top-left, bottom-left, bottom-right, top-right order as everywhere else.
+	pub fn new(top_left: Color, bottom_left: Color, bottom_right: Color, top_right: Color) -> Corners {
+		Corners::Each([top_left, bottom_left, bottom_right, top_right])
+	}
+
+	/// Get the colours in top-left, bottom-left, bottom-right, top-right order, or `None` if the current colour is to be used.
+	pub fn to_array(&self) -> Option<[Color; 4]> {
+		match *self {
+			Corners::None           => None,
+			Corners::Uniform(color) => Some([color; 4]),
+			Corners::Each(corners)  => Some(corners),
+		}
+	}
+}
+
+impl From<Color> for Corners {
+	fn from(color: Color) -> Corners {
+		Corners::Uniform(color)
+	}
+}
+
+/// The colours are in top-left, bottom-left, bottom-right, top-right order.
+impl From<[Color; 4]> for Corners {
+	fn from(corners: [Color; 4]) -> Corners {
+		Corners::Each(corners)
+	}
+}
+
+
+/// A builder for a single [`put_ext()`](fn.put_ext.html) call.
+///
+/// The offset is in pixels, and the animation helpers take their progress in `[0, 1]`,
+/// so they can be driven straight off of a frame counter or a timer.
+///
+/// # Examples
+///
+/// ```
+/// use bear_lib_terminal::Color;
+/// use bear_lib_terminal::terminal::{self, Corners, PutExt};
+/// use bear_lib_terminal::terminal::backend::Headless;
+/// use bear_lib_terminal::geometry::{Point, Size};
+///
+/// // An arrow a quarter of the way from (2, 1) to (3, 1), with 8x16 cells
+/// let arrow = PutExt::new(Point::new(3, 1), '>').slide_from(Point::new(2, 1), Size::new(8, 16), 0.25).color(Color::AMBER);
+/// assert_eq!(arrow.offset_value(), Point{x: -6, y: 0});
+///
+/// terminal::open_with(Headless::new(), "put_ext example", 5, 2).unwrap();
+/// arrow.put();
+/// assert_eq!(terminal::pick(Point::new(3, 1), 0), Ok('>'));
+/// assert_eq!(terminal::pick_foreground_color(Point::new(3, 1), 0), Ok(Color::AMBER));
+///
+/// PutExt::new(Point::new(0, 0), '@').corners(Corners::None).put();
+/// assert_eq!(terminal::pick_foreground_color(Point::new(0, 0), 0), Ok(terminal::state::foreground()));
+/// terminal::close();
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[must_use]
+pub struct PutExt {
+	position: Point,
+	cell: char,
+	offset: Point,
+	corners: Corners,
+}
+
+impl PutExt {
+	/// Start building a call putting the specified character at the specified location, with no offset in the current colour.
+	pub fn new(position: Point, cell: char) -> PutExt {
+		PutExt{
+			position: position,
+			cell: cell,
+			offset: Point{x: 0, y: 0},
+			corners: Corners::None,
+		}
+	}
+
+	/// Offset the cell by the specified amount of pixels.
+	///
+	/// Default: `(0, 0)`.
+	pub fn offset (mut self, offset: Point)    -> Self {self.offset  = offset                 ; self}
+
+	/// Colour the cell's corners.
+	///
+	/// Default: [`Corners::None`](enum.Corners.html#variant.None).
+	pub fn corners(mut self, corners: Corners) -> Self {self.corners = corners                ; self}
+
+	/// Colour the whole cell, equivalent to [`corners()`](#method.corners) with [`Corners::Uniform`](enum.Corners.html#variant.Uniform).
+	pub fn color  (mut self, color: Color)     -> Self {self.corners = Corners::Uniform(color); self}
+
+	/// Offset the cell linearly between the two pixel offsets, `progress` being clamped to `[0, 1]`.
+	pub fn tween(self, from: Point, to: Point, progress: f32) -> Self {
+		let progress = progress.clamp(0.0, 1.0);
+		let lerp = |from: i32, to: i32| from + ((to - from) as f32 * progress).round() as i32;
+		self.offset(Point{x: lerp(from.x, to.x), y: lerp(from.y, to.y)})
+	}
+
+	/// Offset the cell so it appears to move from the specified cell to its own position as `progress` goes from `0` to `1`.
+	///
+	/// `cell_size` is the size of a cell in pixels, usually [`state::cell_size()`](state/fn.cell_size.html).
+	pub fn slide_from(self, from: Point, cell_size: Size, progress: f32) -> Self {
+		let start = Point{x: (from.x - self.position.x) * cell_size.width, y: (from.y - self.position.y) * cell_size.height};
+		self.tween(start, Point{x: 0, y: 0}, progress)
+	}
+
+	/// Offset the cell so it appears to move from its own position to the specified cell as `progress` goes from `0` to `1`.
+	///
+	/// `cell_size` is the size of a cell in pixels, usually [`state::cell_size()`](state/fn.cell_size.html).
+	pub fn slide_to(self, to: Point, cell_size: Size, progress: f32) -> Self {
+		let end = Point{x: (to.x - self.position.x) * cell_size.width, y: (to.y - self.position.y) * cell_size.height};
+		self.tween(Point{x: 0, y: 0}, end, progress)
+	}
+
+	/// Get the pixel offset set so far.
+	pub fn offset_value(&self) -> Point {
+		self.offset
+	}
+
+	/// Put the cell, consuming the builder.
+	pub fn put(self) {
+		terminal::put_ext(self.position, self.offset, self.cell, self.corners);
+	}
+}
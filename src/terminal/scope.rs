@@ -0,0 +1,255 @@
+//! Guards changing the terminal's drawing state and restoring it when dropped.
+//!
+//! Each guard captures the relevant state via the [`state`](../state/index.html) module on construction,
+//! so nested guards unwind in the reverse order, even if the code between panics.
+//!
+//! # Examples
+//!
+//! ```
+//! use bear_lib_terminal::Color;
+//! use bear_lib_terminal::terminal::{self, state};
+//! use bear_lib_terminal::terminal::scope::{LayerGuard, StyleScope};
+//! use bear_lib_terminal::terminal::backend::Headless;
+//!
+//! terminal::open_with(Headless::new(), "terminal::scope example", 40, 10).unwrap();
+//! let red = Color::from_rgb(0xFF, 0x00, 0x00);
+//!
+//! {
+//!     let _layer = LayerGuard::new(2);
+//!     {
+//!         let _style = StyleScope::new().layer(3).fg(red).composition(true);
+//!         assert_eq!((state::layer(), state::foreground()), (3, red));
+//!     }
+//!     assert_eq!(state::layer(), 2);
+//! }
+//! assert_eq!(state::layer(), 0);
+//! terminal::close();
+//! ```
+
+
+use std::any::Any;
+use colors::Color;
+use geometry::Rect;
+use terminal::{self, state};
+
+
+/// Selects a layer, reselecting the previous one when dropped.
+#[must_use]
+#[derive(Debug)]
+pub struct LayerGuard {
+	previous: i32,
+}
+
+/// Sets the foreground and/or background colour, resetting them when dropped.
+#[must_use]
+#[derive(Debug)]
+pub struct ColorGuard {
+	foreground: Option<Color>,
+	background: Option<Color>,
+}
+
+/// Crops the current layer, restoring that layer's previous crop area when dropped.
+#[must_use]
+#[derive(Debug)]
+pub struct CropGuard {
+	layer: i32,
+	previous: Option<Rect>,
+}
+
+/// Toggles composition, resetting it when dropped.
+#[must_use]
+#[derive(Debug)]
+pub struct CompositionGuard {
+	previous: bool,
+}
+
+/// A combination of the guards in this module, constructed and applied one setting at a time.
+///
+/// The settings are applied immediately, in the order they're specified, so e.g. a [`crop()`](#method.crop)
+/// after a [`layer()`](#method.layer) applies to the newly-selected layer.
+///
+/// Everything is restored, in the reverse order, when dropped.
+#[must_use]
+#[derive(Debug, Default)]
+pub struct StyleScope {
+	guards: Vec<Box<dyn Any>>,
+}
+
+
+impl LayerGuard {
+	/// Select the specified layer, see [`terminal::layer()`](../fn.layer.html).
+	pub fn new(index: i32) -> LayerGuard {
+		let previous = state::layer();
+		terminal::layer(index);
+		LayerGuard{
+			previous: previous,
+		}
+	}
+}
+
+impl Drop for LayerGuard {
+	fn drop(&mut self) {
+		terminal::layer(self.previous);
+	}
+}
+
+impl ColorGuard {
+	/// Set the foreground colour, see [`terminal::set_foreground()`](../fn.set_foreground.html).
+	pub fn foreground(color: Color) -> ColorGuard {
+		let previous = state::foreground();
+		terminal::set_foreground(color);
+		ColorGuard{
+			foreground: Some(previous),
+			background: None,
+		}
+	}
+
+	/// Set the background colour, see [`terminal::set_background()`](../fn.set_background.html).
+	pub fn background(color: Color) -> ColorGuard {
+		let previous = state::background();
+		terminal::set_background(color);
+		ColorGuard{
+			foreground: None,
+			background: Some(previous),
+		}
+	}
+
+	/// Set both colours, see [`terminal::set_colors()`](../fn.set_colors.html).
+	pub fn colors(fg: Color, bg: Color) -> ColorGuard {
+		let previous = (state::foreground(), state::background());
+		terminal::set_colors(fg, bg);
+		ColorGuard{
+			foreground: Some(previous.0),
+			background: Some(previous.1),
+		}
+	}
+}
+
+impl Drop for ColorGuard {
+	fn drop(&mut self) {
+		if let Some(color) = self.foreground {
+			terminal::set_foreground(color);
+		}
+		if let Some(color) = self.background {
+			terminal::set_background(color);
+		}
+	}
+}
+
+impl CropGuard {
+	/// Crop the current layer, see [`terminal::crop()`](../fn.crop.html).
+	pub fn new(rect: Rect) -> CropGuard {
+		let guard = CropGuard{
+			layer: state::layer(),
+			previous: state::crop(),
+		};
+		terminal::crop(rect);
+		guard
+	}
+}
+
+impl Drop for CropGuard {
+	fn drop(&mut self) {
+		let _layer = LayerGuard::new(self.layer);
+		terminal::set_crop(self.previous);
+	}
+}
+
+impl CompositionGuard {
+	/// Toggle composition, see [`terminal::composition()`](../fn.composition.html).
+	pub fn new(enable: bool) -> CompositionGuard {
+		let previous = state::composition();
+		terminal::composition(enable);
+		CompositionGuard{
+			previous: previous,
+		}
+	}
+}
+
+impl Drop for CompositionGuard {
+	fn drop(&mut self) {
+		terminal::composition(self.previous);
+	}
+}
+
+impl StyleScope {
+	/// Create a scope changing nothing.
+	pub fn new() -> StyleScope {
+		StyleScope::default()
+	}
+
+	/// Select a layer, see [`LayerGuard`](struct.LayerGuard.html).
+	pub fn layer      (self, index: i32)   -> Self {self.with(LayerGuard::new(index))}
+
+	/// Set the foreground colour, see [`ColorGuard`](struct.ColorGuard.html).
+	pub fn fg         (self, color: Color) -> Self {self.with(ColorGuard::foreground(color))}
+
+	/// Set the background colour, see [`ColorGuard`](struct.ColorGuard.html).
+	pub fn bg         (self, color: Color) -> Self {self.with(ColorGuard::background(color))}
+
+	/// Crop the current layer, see [`CropGuard`](struct.CropGuard.html).
+	pub fn crop       (self, rect: Rect)   -> Self {self.with(CropGuard::new(rect))}
+
+	/// Toggle composition, see [`CompositionGuard`](struct.CompositionGuard.html).
+	pub fn composition(self, enable: bool) -> Self {self.with(CompositionGuard::new(enable))}
+
+	fn with<G: Any>(mut self, guard: G) -> Self {
+		self.guards.push(Box::new(guard));
+		self
+	}
+}
+
+/// Drops the guards last to first, since a `Vec` drops its elements first to last.
+impl Drop for StyleScope {
+	fn drop(&mut self) {
+		while self.guards.pop().is_some() {}
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use std::panic;
+	use super::{ColorGuard, CropGuard, StyleScope};
+	use colors::Color;
+	use geometry::Rect;
+	use terminal::{self, state};
+	use terminal::backend::Headless;
+
+
+	#[test]
+	fn scope_restores_in_reverse() {
+		terminal::open_with(Headless::new(), "scope_restores_in_reverse", 40, 10).unwrap();
+		let (red, blue) = (Color::from_rgb(0xFF, 0x00, 0x00), Color::from_rgb(0x00, 0x00, 0xFF));
+		let (foreground, background) = (state::foreground(), state::background());
+		{
+			// The crop applies to layer 3, and is undone there before layer 3 is deselected
+			let _style = StyleScope::new().fg(red).layer(3).crop(Rect::from_values(0, 0, 20, 5)).bg(blue).fg(blue).composition(true);
+			assert_eq!((state::layer(), state::foreground(), state::background()), (3, blue, blue));
+			assert_eq!(state::crop(), Some(Rect::from_values(0, 0, 20, 5)));
+			assert!(state::composition());
+		}
+		assert_eq!((state::layer(), state::foreground(), state::background()), (0, foreground, background));
+		assert!(!state::composition());
+		terminal::layer(3);
+		assert_eq!(state::crop(), None);
+		terminal::close();
+	}
+
+	#[test]
+	fn guards_restore_when_unwinding() {
+		terminal::open_with(Headless::new(), "guards_restore_when_unwinding", 40, 10).unwrap();
+		terminal::crop(Rect::from_values(1, 1, 5, 5));
+		let foreground = state::foreground();
+		let result = panic::catch_unwind(|| {
+			let _color = ColorGuard::foreground(Color::from_rgb(0xFF, 0x00, 0x00));
+			let _crop = CropGuard::new(Rect::from_values(0, 0, 2, 2));
+			let _style = StyleScope::new().layer(5);
+			panic!("drawing failed");
+		});
+		assert!(result.is_err());
+		assert_eq!((state::layer(), state::foreground()), (0, foreground));
+		assert_eq!(state::crop(), Some(Rect::from_values(1, 1, 5, 5)));
+		terminal::close();
+	}
+}
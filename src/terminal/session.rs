@@ -0,0 +1,218 @@
+//! An RAII [`Terminal`](struct.Terminal.html) handle, closing the terminal it opened when dropped.
+
+
+use std::marker::PhantomData;
+use colors::Color;
+use geometry::{Alignment, Point, Rect, Size};
+use terminal::{self, Corners, EventIterator, Event, Result};
+use terminal::backend::{Backend, Native};
+use terminal::config::ConfigPart;
+
+
+/// An open terminal, closed when dropped.
+///
+/// Only one terminal can be open at a time, so while a `Terminal` is alive any further attempts to open one fail.
+/// The drawing and input functions of the [`terminal`](index.html) module are available as methods, so they can't be called after closing.
+///
+/// The handle is tied to the thread that opened it, as is the terminal's state.
+///
+/// # Examples
+///
+/// ```
+/// use bear_lib_terminal::terminal::{Error, Event, Terminal};
+/// use bear_lib_terminal::terminal::backend::Headless;
+/// use bear_lib_terminal::geometry::Point;
+///
+/// let backend = Headless::new();
+/// {
+///     let terminal = Terminal::open_with(backend.clone(), "RAII example", 20, 2).unwrap();
+///     assert_eq!(Terminal::open_with(Headless::new(), "Second terminal", 20, 2).unwrap_err(), Error::AlreadyOpen);
+///
+///     terminal.print_xy(0, 1, "Hello!");
+///     terminal.refresh();
+///     assert_eq!(terminal.pick(Point::new(1, 1), 0), Ok('e'));
+///     assert_eq!(terminal.wait_event(), Some(Event::Close));
+/// }
+/// assert!(!backend.is_open());
+/// assert!(Terminal::open_with(backend.clone(), "Reopened", 20, 2).is_ok());
+/// ```
+#[derive(Debug)]
+pub struct Terminal {
+	session: u32,
+	not_send: PhantomData<*const ()>,
+}
+
+impl Terminal {
+	/// Create the terminal window of the specified size with the specified title, see [`terminal::open()`](fn.open.html).
+	///
+	/// Fails with [`Error::AlreadyOpen`](enum.Error.html#variant.AlreadyOpen) if a terminal is already open
+	/// and with [`Error::OpenFailed`](enum.Error.html#variant.OpenFailed) if the window couldn't be created.
+	pub fn open(title: &str, width: u32, height: u32) -> Result<Terminal> {
+		Terminal::open_with(Native, title, width, height)
+	}
+
+	/// Equivalent to [`open()`](#method.open), but using the specified [`Backend`](backend/trait.Backend.html),
+	/// see [`terminal::open_with()`](fn.open_with.html).
+	pub fn open_with<B: Backend + 'static>(backend: B, title: &str, width: u32, height: u32) -> Result<Terminal> {
+		terminal::start_session(backend, title, width, height).map(|session| Terminal{
+			session: session,
+			not_send: PhantomData,
+		})
+	}
+
+	/// Close the terminal, equivalent to dropping it.
+	pub fn close(self) {}
+
+	/// See [`terminal::set()`](fn.set.html).
+	pub fn set<T: ConfigPart>(&self, cfg: T) -> Result<()> {
+		terminal::set(cfg)
+	}
+
+	/// See [`terminal::refresh()`](fn.refresh.html).
+	pub fn refresh(&self) {
+		terminal::refresh();
+	}
+
+	/// See [`terminal::clear()`](fn.clear.html).
+	pub fn clear(&self, area: Option<Rect>) {
+		terminal::clear(area);
+	}
+
+	/// See [`terminal::crop()`](fn.crop.html).
+	pub fn crop(&self, rect: Rect) {
+		terminal::crop(rect);
+	}
+
+	/// See [`terminal::layer()`](fn.layer.html).
+	pub fn layer(&self, index: i32) {
+		terminal::layer(index);
+	}
+
+	/// See [`terminal::set_foreground()`](fn.set_foreground.html).
+	pub fn set_foreground(&self, color: Color) {
+		terminal::set_foreground(color);
+	}
+
+	/// See [`terminal::with_foreground()`](fn.with_foreground.html).
+	pub fn with_foreground<R, F: FnOnce() -> R>(&self, color: Color, callback: F) -> R {
+		terminal::with_foreground(color, callback)
+	}
+
+	/// See [`terminal::set_background()`](fn.set_background.html).
+	pub fn set_background(&self, color: Color) {
+		terminal::set_background(color);
+	}
+
+	/// See [`terminal::with_background()`](fn.with_background.html).
+	pub fn with_background<R, F: FnOnce() -> R>(&self, color: Color, callback: F) -> R {
+		terminal::with_background(color, callback)
+	}
+
+	/// See [`terminal::set_colors()`](fn.set_colors.html).
+	pub fn set_colors(&self, fg: Color, bg: Color) {
+		terminal::set_colors(fg, bg);
+	}
+
+	/// See [`terminal::with_colors()`](fn.with_colors.html).
+	pub fn with_colors<R, F: FnOnce() -> R>(&self, fg: Color, bg: Color, callback: F) -> R {
+		terminal::with_colors(fg, bg, callback)
+	}
+
+	/// See [`terminal::composition()`](fn.composition.html).
+	pub fn composition(&self, enable: bool) {
+		terminal::composition(enable);
+	}
+
+	/// See [`terminal::put()`](fn.put.html).
+	pub fn put(&self, point: Point, cell: char) {
+		terminal::put(point, cell);
+	}
+
+	/// See [`terminal::put_xy()`](fn.put_xy.html).
+	pub fn put_xy(&self, x: i32, y: i32, cell: char) {
+		terminal::put_xy(x, y, cell);
+	}
+
+	/// See [`terminal::put_ext()`](fn.put_ext.html).
+	pub fn put_ext(&self, pos: Point, offset: Point, cell: char, corners: Corners) {
+		terminal::put_ext(pos, offset, cell, corners);
+	}
+
+	/// See [`terminal::pick()`](fn.pick.html).
+	pub fn pick(&self, point: Point, index: i32) -> Result<char> {
+		terminal::pick(point, index)
+	}
+
+	/// See [`terminal::pick_foreground_color()`](fn.pick_foreground_color.html).
+	pub fn pick_foreground_color(&self, point: Point, index: i32) -> Result<Color> {
+		terminal::pick_foreground_color(point, index)
+	}
+
+	/// See [`terminal::pick_background_color()`](fn.pick_background_color.html).
+	pub fn pick_background_color(&self, point: Point) -> Result<Color> {
+		terminal::pick_background_color(point)
+	}
+
+	/// See [`terminal::print()`](fn.print.html).
+	pub fn print(&self, point: Point, value: &str) -> Size {
+		terminal::print(point, value)
+	}
+
+	/// See [`terminal::print_xy()`](fn.print_xy.html).
+	pub fn print_xy(&self, x: i32, y: i32, value: &str) -> Size {
+		terminal::print_xy(x, y, value)
+	}
+
+	/// See [`terminal::print_ext()`](fn.print_ext.html).
+	pub fn print_ext(&self, rect: Rect, align: Alignment, value: &str) -> Size {
+		terminal::print_ext(rect, align, value)
+	}
+
+	/// See [`terminal::measure_ext()`](fn.measure_ext.html).
+	pub fn measure_ext(&self, bbox: Size, value: &str) -> Size {
+		terminal::measure_ext(bbox, value)
+	}
+
+	/// See [`terminal::has_input()`](fn.has_input.html).
+	pub fn has_input(&self) -> bool {
+		terminal::has_input()
+	}
+
+	/// See [`terminal::wait_event()`](fn.wait_event.html).
+	pub fn wait_event(&self) -> Option<Event> {
+		terminal::wait_event()
+	}
+
+	/// See [`terminal::events()`](fn.events.html).
+	pub fn events(&self) -> EventIterator {
+		terminal::events()
+	}
+
+	/// See [`terminal::read_event()`](fn.read_event.html).
+	pub fn read_event(&self) -> Option<Event> {
+		terminal::read_event()
+	}
+
+	/// See [`terminal::peek_event()`](fn.peek_event.html).
+	pub fn peek_event(&self) -> Option<Event> {
+		terminal::peek_event()
+	}
+
+	/// See [`terminal::read_str()`](fn.read_str.html).
+	pub fn read_str(&self, point: Point, max: i32) -> Option<String> {
+		terminal::read_str(point, max)
+	}
+
+	/// See [`terminal::delay()`](fn.delay.html).
+	pub fn delay(&self, period: i32) {
+		terminal::delay(period);
+	}
+}
+
+impl Drop for Terminal {
+	fn drop(&mut self) {
+		if terminal::session() == Some(self.session) {
+			terminal::close();
+		}
+	}
+}
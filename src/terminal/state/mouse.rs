@@ -1,6 +1,7 @@
 //! Checking the state of mouse-related properties, namely the mouse cursor's position, `n`-clicks and scrolling.
 
 
+use terminal;
 use geometry::Point;
 use bear_lib_terminal_sys as ffi;
 
@@ -11,21 +12,26 @@ use bear_lib_terminal_sys as ffi;
 ///
 /// Positive values indicate a "down" scroll.
 pub fn scroll() -> i32 {
-	ffi::state(ffi::TK_MOUSE_WHEEL)
+	state(ffi::TK_MOUSE_WHEEL)
 }
 
 /// Get the mouse cursor's position in cells.
 pub fn position() -> Point {
-	Point::new(ffi::state(ffi::TK_MOUSE_X), ffi::state(ffi::TK_MOUSE_Y))
+	Point::new(state(ffi::TK_MOUSE_X), state(ffi::TK_MOUSE_Y))
 }
 
 /// Get the mouse cursor's position in pixels.
 pub fn pixel_position() -> Point {
-	Point::new(ffi::state(ffi::TK_MOUSE_PIXEL_X), ffi::state(ffi::TK_MOUSE_PIXEL_Y))
+	Point::new(state(ffi::TK_MOUSE_PIXEL_X), state(ffi::TK_MOUSE_PIXEL_Y))
 }
 
-/// Amount of fast consecutive clicks for the [`Event::KeyPressed`](../../enum.Event.html#variant.KeyPressed)
-/// with [`key: Mouse*`](../../enum.Event.html#variant.KeyPressed).
+/// Amount of fast consecutive clicks for the last [`Event::MouseDown`](../../enum.Event.html#variant.MouseDown)
+/// or [`Event::MouseUp`](../../enum.Event.html#variant.MouseUp), which also carry it.
 pub fn clicks() -> i32 {
-	ffi::state(ffi::TK_MOUSE_CLICKS)
+	state(ffi::TK_MOUSE_CLICKS)
+}
+
+
+fn state(slot: i32) -> i32 {
+	terminal::with_backend(|backend| backend.state(slot))
 }
@@ -0,0 +1,137 @@
+//! A [`Viewport`](struct.Viewport.html) camera mapping between world and screen coordinates.
+
+
+use std::cmp;
+use geometry::{Offset, Point, Rect};
+use terminal::state;
+
+
+/// A camera showing part of a world larger than the terminal in an area of the screen.
+///
+/// The viewport never scrolls past the world's edges; a world smaller than the screen area is centered in it.
+///
+/// # Examples
+///
+/// ```
+/// # use bear_lib_terminal::Viewport;
+/// # use bear_lib_terminal::geometry::{Offset, Point, Rect};
+/// // A 100x50 map shown below a 1-line status bar on an 80x25 terminal
+/// let mut camera = Viewport::new(Rect::from_values(0, 0, 100, 50), Rect::from_values(0, 1, 80, 24));
+///
+/// camera.center_on(Point::new(50, 25));
+/// assert_eq!(camera.visible(), Rect::from_values(10, 13, 80, 24));
+/// assert_eq!(camera.to_screen(Point::new(50, 25)), Some(Point::new(40, 13)));
+/// assert_eq!(camera.to_world(Point::new(40, 13)), Some(Point::new(50, 25)));
+/// assert_eq!(camera.to_screen(Point::new(5, 25)), None);
+/// assert_eq!(camera.to_world(Point::new(40, 0)), None);
+///
+/// camera.center_on(Point::new(99, 0));
+/// assert_eq!(camera.origin(), Point::new(20, 0));
+/// camera.scroll_by(Offset::new(-5, 100));
+/// assert_eq!(camera.origin(), Point::new(15, 26));
+///
+/// let mut small = Viewport::new(Rect::from_values(0, 0, 10, 10), Rect::from_values(0, 0, 20, 10));
+/// small.center_on(Point::new(0, 0));
+/// assert_eq!(small.to_screen(Point::new(0, 0)), Some(Point::new(5, 0)));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Viewport {
+	world: Rect,
+	screen: Rect,
+	origin: Point,
+}
+
+impl Viewport {
+	/// Construct a viewport showing the specified world in the specified screen area, scrolled to the world's top-left corner.
+	pub fn new(world: Rect, screen: Rect) -> Viewport {
+		let mut viewport = Viewport{
+			world: world,
+			screen: screen,
+			origin: world.top_left,
+		};
+		viewport.scroll_to(world.top_left);
+		viewport
+	}
+
+	/// Get the world's bounds.
+	pub fn world(&self) -> Rect {
+		self.world
+	}
+
+	/// Get the screen area the world's shown in.
+	pub fn screen(&self) -> Rect {
+		self.screen
+	}
+
+	/// Get the world point shown in the screen area's top-left corner.
+	pub fn origin(&self) -> Point {
+		self.origin
+	}
+
+	/// Get the part of the world shown, which extends beyond the world's bounds if it's smaller than the screen area.
+	pub fn visible(&self) -> Rect {
+		Rect::from_size(self.origin, self.screen.size)
+	}
+
+	/// Change the world's bounds, scrolling back within them if needed.
+	pub fn set_world(&mut self, world: Rect) {
+		self.world = world;
+		let origin = self.origin;
+		self.scroll_to(origin);
+	}
+
+	/// Change the screen area, e.g. after the terminal's been resized, scrolling back within the world if needed.
+	pub fn set_screen(&mut self, screen: Rect) {
+		self.screen = screen;
+		let origin = self.origin;
+		self.scroll_to(origin);
+	}
+
+	/// Scroll so that the specified world point is shown in the screen area's top-left corner, as far as the world's bounds allow.
+	pub fn scroll_to(&mut self, origin: Point) {
+		let clamp = |origin: i32, world_start: i32, world_length: i32, screen_length: i32| if world_length <= screen_length {
+			world_start - (screen_length - world_length) / 2
+		} else {
+			cmp::max(world_start, cmp::min(origin, world_start + world_length - screen_length))
+		};
+
+		self.origin = Point::new(clamp(origin.x, self.world.top_left.x, self.world.size.width, self.screen.size.width),
+		                         clamp(origin.y, self.world.top_left.y, self.world.size.height, self.screen.size.height));
+	}
+
+	/// Scroll by the specified offset, as far as the world's bounds allow.
+	pub fn scroll_by(&mut self, offset: Offset) {
+		let origin = self.origin + offset;
+		self.scroll_to(origin);
+	}
+
+	/// Scroll so that the specified world point is in the middle of the screen area, as far as the world's bounds allow.
+	pub fn center_on(&mut self, target: Point) {
+		let center = self.screen.center();
+		self.scroll_to(target - (center - self.screen.top_left));
+	}
+
+	/// Get the screen point showing the specified world point, or `None` if it isn't shown.
+	pub fn to_screen(&self, point: Point) -> Option<Point> {
+		if self.world.contains(point) && self.visible().contains(point) {
+			Some(self.screen.top_left + (point - self.origin))
+		} else {
+			None
+		}
+	}
+
+	/// Get the world point shown at the specified screen point, or `None` if it's outside the screen area or the world.
+	pub fn to_world(&self, point: Point) -> Option<Point> {
+		let world = self.origin + (point - self.screen.top_left);
+		if self.screen.contains(point) && self.world.contains(world) {
+			Some(world)
+		} else {
+			None
+		}
+	}
+
+	/// Get the world point under the mouse cursor, if any, as per [`state::mouse::position()`](terminal/state/mouse/fn.position.html).
+	pub fn mouse(&self) -> Option<Point> {
+		self.to_world(state::mouse::position())
+	}
+}